@@ -12,56 +12,191 @@
 
 import Foundation
 
+// MARK: - Paragraph Boundary
+
+/// Which line breaks stop the active region from extending further back
+public enum ParagraphBoundary: String, Codable, CaseIterable, Sendable {
+    /// Newlines are treated as ordinary whitespace
+    case none
+    /// Stop at the nearest preceding blank line (`\n\n`)
+    case blankLine
+    /// Stop at the nearest preceding newline
+    case newline
+}
+
+// MARK: - Region Scope
+
+/// How much text behind the caret the active region covers
+public enum RegionScope: String, Codable, CaseIterable, Sendable {
+    /// Up to `targetWords` words trailing the caret
+    case trailingWords
+    /// Only the word the caret just left; empty while the caret is mid-word
+    case lastWord
+}
+
+// MARK: - Region Explanation
+
+/// Which rule terminated the active region on its start side
+public enum RegionBoundaryReason: String, Codable, CaseIterable, Sendable {
+    case bufferStart
+    case paragraph
+    case sentence
+    case maxLength
+    case wordLimit
+    case enclosure
+}
+
+/// An active region plus the reason its start landed where it did
+public struct RegionExplanation: Equatable, Sendable {
+    public let region: TextRegion
+    public let startReason: RegionBoundaryReason
+}
+
 // MARK: - Active Region Policy
 
 /// Computes the active region for correction processing
 public struct ActiveRegionPolicy: Sendable {
     public let targetWords: Int
     public let maxCharacters: Int
+    /// Line breaks the region never crosses (distinct thoughts in multiline fields)
+    public let paragraphBoundary: ParagraphBoundary
+    public let scope: RegionScope
+    /// Characters that split words in addition to whitespace.
+    /// nil uses Unicode word boundaries; an empty set splits on whitespace only.
+    public let wordSeparators: Set<Character>?
+    /// Start the region inside the innermost quote/parenthesis/bracket still open at the caret
+    public let anchorToEnclosure: Bool
     
-    public init(targetWords: Int = 20, maxCharacters: Int = 500) {
+    public init(
+        targetWords: Int = 20,
+        maxCharacters: Int = 500,
+        paragraphBoundary: ParagraphBoundary = .blankLine,
+        scope: RegionScope = .trailingWords,
+        wordSeparators: Set<Character>? = nil,
+        anchorToEnclosure: Bool = false
+    ) {
         self.targetWords = targetWords
         self.maxCharacters = maxCharacters
+        self.paragraphBoundary = paragraphBoundary
+        self.scope = scope
+        self.wordSeparators = wordSeparators
+        self.anchorToEnclosure = anchorToEnclosure
     }
     
     /// Compute the active region given text and caret position
     public func computeRegion(text: String, caret: Int) -> TextRegion {
+        explainRegion(text: text, caret: caret).region
+    }
+    
+    /// Compute the active region clamped to the visual line containing the caret
+    /// - Parameter lineStarts: Offsets where each soft-wrapped line begins, as laid out by the host
+    public func computeRegion(text: String, caret: Int, lineStarts: [Int]) -> TextRegion {
+        let region = computeRegion(text: text, caret: caret)
+        let lineStart = lineStarts.filter { $0 <= region.end }.max() ?? 0
+        return TextRegion(start: max(region.start, lineStart), end: region.end)
+    }
+    
+    /// Compute the active region along with the rule that chose its start (developer diagnostic)
+    public func explainRegion(text: String, caret: Int) -> RegionExplanation {
+        explainRegion(text: text, caret: caret, windowed: true)
+    }
+    
+    /// - Parameter windowed: false tokenizes everything before the caret (reference for tests)
+    func explainRegion(text: String, caret: Int, windowed: Bool) -> RegionExplanation {
         guard caret > 0, !text.isEmpty else {
-            return TextRegion(start: 0, end: 0)
+            return RegionExplanation(region: TextRegion(start: 0, end: 0), startReason: .bufferStart)
         }
         
         let safeCaret = min(caret, text.count)
-        let textBeforeCaret = String(text.prefix(safeCaret))
+        // Only the tail that can end up in the region (plus sentence-alignment lookback) is
+        // tokenized and searched for boundaries, so that work is bounded by maxCharacters.
+        // Locating the tail still walks every Character before the caret.
+        let windowStart = windowed ? max(0, safeCaret - maxCharacters - Self.sentenceLookback) : 0
+        let window = String(text.dropFirst(windowStart).prefix(safeCaret - windowStart))
+        let windowCaret = safeCaret - windowStart
+        let isTruncated = windowStart > 0
+        let toDocument = { (region: TextRegion) in
+            TextRegion(start: region.start + windowStart, end: region.end + windowStart)
+        }
+        let paragraphStart = findParagraphStart(in: window)
         
         // Find word boundaries going backwards
-        let words = findWordBoundaries(in: textBeforeCaret)
+        let words = findWordBoundaries(in: window)
+        
+        if scope == .lastWord {
+            return RegionExplanation(
+                region: toDocument(lastWordRegion(words: words, caret: windowCaret, paragraphStart: paragraphStart)),
+                startReason: .wordLimit
+            )
+        }
         
         guard !words.isEmpty else {
-            return TextRegion(start: 0, end: safeCaret)
+            // Nothing to count words by, so only the paragraph and length cap bound the region
+            let cappedStart = max(0, windowCaret - maxCharacters)
+            return RegionExplanation(
+                region: toDocument(TextRegion(start: max(paragraphStart, cappedStart), end: windowCaret)),
+                startReason: paragraphStart > cappedStart ? .paragraph : (cappedStart > 0 ? .maxLength : .bufferStart)
+            )
         }
         
         // Take up to targetWords words
         let wordCount = min(targetWords, words.count)
         let startWordIndex = max(0, words.count - wordCount)
-        let startOffset = words[startWordIndex].start
+        // Text before a truncated window always holds more words, so the length cap decides
+        let startOffset = isTruncated && startWordIndex == 0 ? 0 : words[startWordIndex].start
         
         // Clamp to maxCharacters
-        let clampedStart = max(startOffset, safeCaret - maxCharacters)
+        let clampedStart = max(startOffset, windowCaret - maxCharacters)
         
         // Try to align to sentence boundary if possible
-        let alignedStart = alignToSentenceBoundary(in: textBeforeCaret, nearIndex: clampedStart)
+        let alignedStart = alignToSentenceBoundary(in: window, nearIndex: clampedStart)
+        
+        let regionStart = max(alignedStart, paragraphStart) + windowStart
+        // Quote parity depends on everything before the caret, so this scan can't be windowed
+        if anchorToEnclosure,
+           let enclosureStart = findEnclosureStart(in: String(text.prefix(safeCaret))),
+           enclosureStart > regionStart {
+            return RegionExplanation(
+                region: TextRegion(start: enclosureStart, end: safeCaret),
+                startReason: .enclosure
+            )
+        }
+        
+        let reason: RegionBoundaryReason
+        if paragraphStart > alignedStart {
+            reason = .paragraph
+        } else if alignedStart != clampedStart {
+            reason = .sentence
+        } else if clampedStart > startOffset {
+            reason = .maxLength
+        } else if startWordIndex > 0 {
+            reason = .wordLimit
+        } else {
+            reason = .bufferStart
+        }
         
-        return TextRegion(start: alignedStart, end: safeCaret)
+        // Never reach back across a paragraph break
+        return RegionExplanation(
+            region: TextRegion(start: regionStart, end: safeCaret),
+            startReason: reason
+        )
     }
     
     // MARK: - Private Helpers
     
+    /// How far before the length cap sentence alignment may look for a boundary
+    private static let sentenceLookback = 50
+    
     private struct WordBoundary {
         let start: Int
         let end: Int
     }
     
     private func findWordBoundaries(in text: String) -> [WordBoundary] {
+        if let wordSeparators {
+            return findWordBoundaries(in: text, separators: wordSeparators)
+        }
+        
         var boundaries: [WordBoundary] = []
         
         // Use natural language word tokenization
@@ -75,9 +210,94 @@ public struct ActiveRegionPolicy: Sendable {
         return boundaries
     }
     
+    /// Words as maximal runs of characters that are neither whitespace nor a separator
+    private func findWordBoundaries(in text: String, separators: Set<Character>) -> [WordBoundary] {
+        var boundaries: [WordBoundary] = []
+        var wordStart: Int?
+        
+        for (offset, char) in text.enumerated() {
+            let splits = char.isWhitespace || separators.contains(char)
+            if splits, let start = wordStart {
+                boundaries.append(WordBoundary(start: start, end: offset))
+                wordStart = nil
+            } else if !splits, wordStart == nil {
+                wordStart = offset
+            }
+        }
+        if let start = wordStart {
+            boundaries.append(WordBoundary(start: start, end: text.count))
+        }
+        
+        return boundaries
+    }
+    
+    /// The word just finished before a trailing separator, or an empty region at the caret
+    private func lastWordRegion(words: [WordBoundary], caret: Int, paragraphStart: Int) -> TextRegion {
+        guard let last = words.last, last.end < caret, last.start >= paragraphStart else {
+            return TextRegion(start: caret, end: caret)
+        }
+        return TextRegion(start: last.start, end: last.end)
+    }
+    
+    /// Offset just past the nearest preceding paragraph break, or 0 if none
+    private func findParagraphStart(in text: String) -> Int {
+        guard paragraphBoundary != .none else { return 0 }
+        
+        var offset = text.count
+        var previousWasNewline = false
+        
+        for char in text.reversed() {
+            if char.isNewline {
+                switch paragraphBoundary {
+                case .newline:
+                    return offset
+                case .blankLine where previousWasNewline:
+                    return offset + 1
+                default:
+                    previousWasNewline = true
+                }
+            } else {
+                previousWasNewline = false
+            }
+            offset -= 1
+        }
+        
+        return 0
+    }
+    
+    /// Offset just past the innermost opener (`(`, `[`, `“`, `"`) left unclosed before the caret
+    private func findEnclosureStart(in text: String) -> Int? {
+        let closers: [Character: Character] = ["(": ")", "[": "]", "“": "”"]
+        var pendingClosers: [Character: Int] = [:]
+        var offset = text.count
+        var nearest: Int?
+        
+        for char in text.reversed() {
+            offset -= 1
+            if closers.values.contains(char) {
+                pendingClosers[char, default: 0] += 1
+            } else if let closer = closers[char] {
+                if pendingClosers[closer, default: 0] > 0 {
+                    pendingClosers[closer, default: 0] -= 1
+                } else {
+                    nearest = offset + 1
+                    break
+                }
+            }
+        }
+        
+        // Straight quotes don't say which side they open; an odd count means the last one is open
+        let quoteOffsets = text.enumerated().filter { $0.element == "\"" }.map(\.offset)
+        if quoteOffsets.count % 2 == 1, let lastQuote = quoteOffsets.last {
+            nearest = max(nearest ?? 0, lastQuote + 1)
+        }
+        
+        return nearest
+    }
+    
     private func alignToSentenceBoundary(in text: String, nearIndex: Int) -> Int {
         // Look for sentence-ending punctuation followed by space near the index
-        let searchStart = max(0, nearIndex - 50)
+        let searchStart = max(0, nearIndex - Self.sentenceLookback)
         let searchRange = searchStart..<min(nearIndex + 20, text.count)
         
         guard searchRange.lowerBound < searchRange.upperBound else {
@@ -0,0 +1,157 @@
+/*╔══════════════════════════════════════════════════════════════╗
+  ║  ░  C O R R E C T I O N   O U T P U T  ░░░░░░░░░░░░░░░░░░░  ║
+  ║                                                              ║
+  ║   Alternative representations of correction diffs for       ║
+  ║   hosts that can't consume absolute spans directly.         ║
+  ║                                                              ║
+  ╚══════════════════════════════════════════════════════════════╝
+  • WHAT ▸ Diff → delta/markup/track-changes conversions
+  • WHY  ▸ Collaborative and review UIs need position-stable output
+  • HOW  ▸ Pure functions over sorted, non-overlapping diffs
+*/
+
+import Foundation
+
+// MARK: - Delta Operations
+
+/// A Quill-style delta operation. Lengths count Characters, like all core offsets.
+public enum DeltaOperation: Equatable, Codable, Sendable {
+    case retain(Int)
+    case delete(Int)
+    case insert(String)
+}
+
+/// Express diffs as retain/delete/insert operations that compose with concurrent edits
+/// - Parameter textLength: Length of the text the diffs target, to reject spans past its end
+/// - Returns: nil if the diffs overlap, are inverted, or fall outside the text
+public func makeDelta(from diffs: [CorrectionDiff], textLength: Int? = nil) -> [DeltaOperation]? {
+    var operations: [DeltaOperation] = []
+    var cursor = 0
+    
+    for diff in diffs.sorted(by: { $0.start < $1.start }) {
+        guard diff.start >= cursor, diff.start <= diff.end, diff.end <= textLength ?? .max else { return nil }
+        
+        if diff.start > cursor {
+            operations.append(.retain(diff.start - cursor))
+        }
+        if diff.end > diff.start {
+            operations.append(.delete(diff.end - diff.start))
+        }
+        if !diff.text.isEmpty {
+            operations.append(.insert(diff.text))
+        }
+        cursor = diff.end
+    }
+    
+    return operations
+}
+
+/// Apply delta operations to text; untouched trailing text is retained implicitly
+/// - Returns: nil if an operation runs past the end of the text
+public func applyDelta(_ operations: [DeltaOperation], to text: String) -> String? {
+    var result = ""
+    var remaining = Substring(text)
+    
+    for operation in operations {
+        switch operation {
+        case .retain(let count):
+            guard count <= remaining.count else { return nil }
+            result += remaining.prefix(count)
+            remaining = remaining.dropFirst(count)
+        case .delete(let count):
+            guard count <= remaining.count else { return nil }
+            remaining = remaining.dropFirst(count)
+        case .insert(let inserted):
+            result += inserted
+        }
+    }
+    
+    return result + remaining
+}
+
+// MARK: - Annotated Markup
+
+/// Corrected text with each applied diff wrapped in a `<correction>` tag carrying the
+/// original text and category, so screen readers can announce what changed
+/// - Returns: nil if the diffs overlap or fall outside the text
+public func annotatedText(_ text: String, diffs: [CorrectionDiff]) -> String? {
+    let characters = Array(text)
+    var result = ""
+    var cursor = 0
+    
+    for diff in diffs.sorted(by: { $0.start < $1.start }) {
+        guard diff.start >= cursor, diff.start <= diff.end, diff.end <= characters.count else { return nil }
+        
+        let original = String(characters[diff.start..<diff.end])
+        result += escapeMarkup(String(characters[cursor..<diff.start]))
+        result += "<correction original=\"\(escapeMarkup(original))\" category=\"\(diff.category.rawValue)\">"
+        result += escapeMarkup(diff.text)
+        result += "</correction>"
+        cursor = diff.end
+    }
+    
+    return result + escapeMarkup(String(characters[cursor...]))
+}
+
+/// Remove `<correction>` tags from annotated text, yielding the plain corrected text
+public func stripAnnotations(_ annotated: String) -> String {
+    annotated
+        .replacingOccurrences(of: #"</?correction[^>]*>"#, with: "", options: .regularExpression)
+        .replacingOccurrences(of: "&lt;", with: "<")
+        .replacingOccurrences(of: "&gt;", with: ">")
+        .replacingOccurrences(of: "&quot;", with: "\"")
+        .replacingOccurrences(of: "&amp;", with: "&")
+}
+
+// MARK: - Track Changes
+
+/// Delimiters wrapped around removed and inserted text in track-changes markup
+public struct TrackChangesSyntax: Equatable, Sendable {
+    public let deletionOpen: String
+    public let deletionClose: String
+    public let insertionOpen: String
+    public let insertionClose: String
+    
+    public init(deletionOpen: String, deletionClose: String, insertionOpen: String, insertionClose: String) {
+        self.deletionOpen = deletionOpen
+        self.deletionClose = deletionClose
+        self.insertionOpen = insertionOpen
+        self.insertionClose = insertionClose
+    }
+    
+    /// `~~teh~~the`: struck-through original followed by the replacement
+    public static let markdown = TrackChangesSyntax(deletionOpen: "~~", deletionClose: "~~", insertionOpen: "", insertionClose: "")
+    /// `{--teh--}{++the++}`
+    public static let criticMarkup = TrackChangesSyntax(deletionOpen: "{--", deletionClose: "--}", insertionOpen: "{++", insertionClose: "++}")
+}
+
+/// Text showing each diff as its marked deletion followed by its marked insertion, for
+/// review UIs. Pass the region text with region-relative diffs to mark up just the region.
+/// - Returns: nil if the diffs overlap or fall outside the text
+public func trackChangesText(_ text: String, diffs: [CorrectionDiff], syntax: TrackChangesSyntax = .markdown) -> String? {
+    let characters = Array(text)
+    var result = ""
+    var cursor = 0
+    
+    for diff in diffs.sorted(by: { $0.start < $1.start }) {
+        guard diff.start >= cursor, diff.start <= diff.end, diff.end <= characters.count else { return nil }
+        
+        result += String(characters[cursor..<diff.start])
+        if diff.start < diff.end {
+            result += syntax.deletionOpen + String(characters[diff.start..<diff.end]) + syntax.deletionClose
+        }
+        if !diff.text.isEmpty {
+            result += syntax.insertionOpen + diff.text + syntax.insertionClose
+        }
+        cursor = diff.end
+    }
+    
+    return result + String(characters[cursor...])
+}
+
+private func escapeMarkup(_ text: String) -> String {
+    text.replacingOccurrences(of: "&", with: "&amp;")
+        .replacingOccurrences(of: "<", with: "&lt;")
+        .replacingOccurrences(of: ">", with: "&gt;")
+        .replacingOccurrences(of: "\"", with: "&quot;")
+}
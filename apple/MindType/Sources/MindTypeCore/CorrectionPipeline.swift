@@ -12,6 +12,14 @@
 
 import Foundation
 
+// MARK: - Confidence Scorer
+
+/// Scores a stage candidate given the original span and its proposed replacement (0...1)
+public typealias ConfidenceScorer = @Sendable (_ original: String, _ candidate: String) -> Double
+
+/// Receives each suggested correction, in the result's offset space
+public typealias AuditHandler = @Sendable (CorrectionDiff) -> Void
+
 // MARK: - Correction Pipeline
 
 /// The main correction pipeline that orchestrates the three stages
@@ -19,6 +27,26 @@ public actor CorrectionPipeline {
     private let lmAdapter: any LMAdapter
     private let config: PipelineConfiguration
     private let regionPolicy: ActiveRegionPolicy
+    /// Minimum confidence a stage candidate needs; starts at `config.confidenceThreshold`
+    public private(set) var confidenceThreshold: Double
+    
+    /// Cache and history per editor field, evicted least-recently-used first
+    private var fieldStates: [String: FieldState] = [:]
+    private var fieldRecency: [String] = []
+    
+    /// Field used when callers don't pass a `fieldId`
+    private static let defaultFieldID = ""
+    
+    /// Host-supplied scoring that replaces the built-in confidence for a stage
+    private var confidenceScorers: [CorrectionStage: ConfidenceScorer] = [:]
+    /// Observer told about every correction a computed wave suggests
+    private var auditHandler: AuditHandler?
+    
+    /// Durations of the most recent computed waves, oldest first
+    private var recentLatencies: [Double] = []
+    private static let latencyWindowSize = 100
+    /// Computed waves a span's churn is remembered without another correction there
+    private static let churnWindowWaves = 20
     
     public init(
         lmAdapter: any LMAdapter,
@@ -26,52 +54,127 @@ public actor CorrectionPipeline {
     ) {
         self.lmAdapter = lmAdapter
         self.config = config
+        self.confidenceThreshold = config.confidenceThreshold
         // Create region policy from config — ensures activeRegionWords is actually used
-        self.regionPolicy = ActiveRegionPolicy(targetWords: config.activeRegionWords)
+        self.regionPolicy = ActiveRegionPolicy(
+            targetWords: config.activeRegionWords,
+            paragraphBoundary: config.paragraphBoundary,
+            scope: config.regionScope,
+            wordSeparators: config.wordSeparators,
+            anchorToEnclosure: config.anchorToEnclosure
+        )
+    }
+    
+    /// The region policy waves use, built from the configuration; hosts that highlight the
+    /// band themselves (e.g. `TypingMonitor.activeRegion(using:)`) should pass this
+    public nonisolated var activeRegionPolicy: ActiveRegionPolicy {
+        regionPolicy
     }
     
     /// Run the correction wave on the given text
     /// 
     /// Returns a single cumulative diff representing all corrections applied.
     /// This avoids the issue of overlapping diffs from multiple stages.
+    /// - Parameters:
+    ///   - fieldId: Keeps cache and history separate per editor field
+    ///   - editableRange: Only this span may be corrected; the active region is clamped to it
+    ///   - baseText: The text the host will apply corrections to, when it lags behind the live
+    ///     `text`; the result is marked `stale` if the two differ inside the active region
     public func runCorrectionWave(
         text: String,
         caret: Int,
-        toneTarget: ToneTarget? = nil
+        toneTarget: ToneTarget? = nil,
+        fieldId: String? = nil,
+        editableRange: TextRegion? = nil,
+        baseText: String? = nil
     ) async throws -> CorrectionWaveResult {
+        var result = try await computeWave(
+            text: text,
+            caret: caret,
+            toneTarget: toneTarget,
+            fieldId: fieldId,
+            editableRange: editableRange
+        )
+        if let baseText {
+            let region = result.rebased(to: .document).activeRegion
+            result.stale = region.end > baseText.count
+                || extractSpan(from: baseText, region: region) != extractSpan(from: text, region: region)
+        }
+        return result
+    }
+    
+    private func computeWave(
+        text: String,
+        caret: Int,
+        toneTarget: ToneTarget?,
+        fieldId: String?,
+        editableRange: TextRegion?
+    ) async throws -> CorrectionWaveResult {
+        let fieldKey = fieldId ?? Self.defaultFieldID
+        
+        // Bound CPU during rapid typing by serving the last result until the interval passes
+        if config.minProcessIntervalMs > 0,
+           let state = fieldStates[fieldKey],
+           let lastWave = state.lastWave,
+           let lastKey = lastWave.key,
+           let computedAt = state.lastComputedAt,
+           Date().timeIntervalSince(computedAt) * 1000 < Double(config.minProcessIntervalMs) {
+            touchField(fieldKey)
+            var limitedResult = replay(lastWave.result, computedFor: lastKey.text, onto: text, caret: caret)
+            limitedResult.rateLimited = true
+            return limitedResult
+        }
+        
         let startTime = Date()
         
-        // Compute active region on original text
-        let activeRegion = regionPolicy.computeRegion(text: text, caret: caret)
+        // Compute active region on original text, never extending into read-only text
+        var activeRegion = regionPolicy.computeRegion(text: text, caret: caret)
+        if let editableRange {
+            activeRegion = TextRegion(
+                start: max(activeRegion.start, editableRange.start),
+                end: min(activeRegion.end, editableRange.end)
+            )
+        }
         
         guard !activeRegion.isEmpty else {
-            return CorrectionWaveResult(
-                diffs: [],
-                activeRegion: activeRegion,
-                durationMs: Date().timeIntervalSince(startTime) * 1000
-            )
+            return emptyWave(region: activeRegion, startTime: startTime, fieldKey: fieldKey)
         }
         
         // Ensure region is caret-safe
         guard isCaretSafe(region: activeRegion, caret: caret) else {
-            return CorrectionWaveResult(
-                diffs: [],
-                activeRegion: TextRegion(start: caret, end: caret),
-                durationMs: Date().timeIntervalSince(startTime) * 1000
-            )
+            return emptyWave(region: TextRegion(start: caret, end: caret), startTime: startTime, fieldKey: fieldKey)
+        }
+        
+        // Skip the LM entirely when the text, caret and tone are exactly as last time
+        let effectiveTone = toneTarget ?? config.toneTarget
+        let cacheKey = WaveCacheKey(
+            region: activeRegion,
+            text: text,
+            caret: caret,
+            toneTarget: effectiveTone
+        )
+        if let lastWave = fieldStates[fieldKey]?.lastWave, lastWave.key == cacheKey {
+            touchField(fieldKey)
+            var cachedResult = lastWave.result
+            cachedResult.cached = true
+            return cachedResult
         }
         
         var stageDiffs: [CorrectionDiff] = []  // Track individual stage contributions
         var currentText = text
         var currentRegion = activeRegion
         var currentCaret = caret
+        var diagnostics = WaveDiagnostics()
+        var stagesAttempted = 2
         
         // Stage 1: Noise (typo fixes)
-        if let noiseDiff = try await runNoiseStage(
-            text: currentText,
-            caret: currentCaret,
-            region: currentRegion
-        ), noiseDiff.confidence >= config.confidenceThreshold {
+        if let noiseDiff = try await isolated(.noise, recording: &diagnostics, {
+            try await runNoiseStage(
+                text: currentText,
+                caret: currentCaret,
+                region: currentRegion
+            )
+        }), noiseDiff.confidence >= confidenceThreshold {
             stageDiffs.append(noiseDiff)
             if let result = applyDiff(text: currentText, diff: noiseDiff, caret: currentCaret) {
                 // Update tracking variables for next stage
@@ -87,11 +190,13 @@ public actor CorrectionPipeline {
         }
         
         // Stage 2: Context (grammar/coherence) — uses updated region
-        if let contextDiff = try await runContextStage(
-            text: currentText,
-            caret: currentCaret,
-            region: currentRegion
-        ), contextDiff.confidence >= config.confidenceThreshold {
+        if let contextDiff = try await isolated(.context, recording: &diagnostics, {
+            try await runContextStage(
+                text: currentText,
+                caret: currentCaret,
+                region: currentRegion
+            )
+        }), contextDiff.confidence >= confidenceThreshold {
             stageDiffs.append(contextDiff)
             if let result = applyDiff(text: currentText, diff: contextDiff, caret: currentCaret) {
                 let lengthDelta = contextDiff.lengthDelta
@@ -105,14 +210,16 @@ public actor CorrectionPipeline {
         }
         
         // Stage 3: Tone (optional style adjustment) — uses updated region
-        let effectiveTone = toneTarget ?? config.toneTarget
         if effectiveTone != .none {
-            if let toneDiff = try await runToneStage(
-                text: currentText,
-                caret: currentCaret,
-                region: currentRegion,
-                toneTarget: effectiveTone
-            ), toneDiff.confidence >= config.confidenceThreshold {
+            stagesAttempted += 1
+            if let toneDiff = try await isolated(.tone, recording: &diagnostics, {
+                try await runToneStage(
+                    text: currentText,
+                    caret: currentCaret,
+                    region: currentRegion,
+                    toneTarget: effectiveTone
+                )
+            }), toneDiff.confidence >= confidenceThreshold {
                 stageDiffs.append(toneDiff)
                 if let result = applyDiff(text: currentText, diff: toneDiff, caret: currentCaret) {
                     let lengthDelta = toneDiff.lengthDelta
@@ -127,6 +234,11 @@ public actor CorrectionPipeline {
             }
         }
         
+        // A failing stage is skipped, but if nothing ran there is nothing to report
+        if diagnostics.failures.count == stagesAttempted, let first = diagnostics.failures.first {
+            throw first.error
+        }
+        
         let durationMs = Date().timeIntervalSince(startTime) * 1000
         
         // Track which stages actually made changes
@@ -136,6 +248,8 @@ public actor CorrectionPipeline {
         // This avoids overlapping diffs problem when caller applies them
         var finalDiffs: [CorrectionDiff] = []
         
+        var attemptedCorrection: SpanCorrection?
+        
         if currentText != text && !stageDiffs.isEmpty {
             // Extract what the final corrected region looks like
             let finalRegionText = extractSpan(from: currentText, region: currentRegion)
@@ -146,26 +260,324 @@ public actor CorrectionPipeline {
                 // Use activeRegion boundaries (original text positions) as diff applies to original text
                 // Extract finalRegionText using currentRegion which tracks where the region ended up after all changes
                 // Report the first stage that made changes (typically noise/typos) as the primary correction type
-                let cumulativeDiff = CorrectionDiff(
-                    start: activeRegion.start,
-                    end: activeRegion.end,
-                    text: finalRegionText,
-                    stage: stageDiffs.first?.stage ?? .noise,
-                    confidence: stageDiffs.map(\.confidence).min() ?? 0.9
-                )
-                finalDiffs.append(cumulativeDiff)
+                let primaryStage = stageDiffs.first?.stage ?? .noise
+                let confidence = stageDiffs.map(\.confidence).min() ?? 0.9
+                // Any tone or grammar contribution makes the whole cumulative edit a suggestion
+                let safeToAutoApply = stageDiffs.allSatisfy {
+                    config.autoApplyPolicy.allows(stage: $0.stage, category: $0.category, confidence: confidence)
+                }
+                let edit = RegionEdit(from: originalRegionText, to: finalRegionText)
+                // Hosts may refuse an edit that ends exactly where the user is typing. Judge by
+                // what changed, not by the region end the untrimmed diff spans to.
+                let changedEnd = activeRegion.end - edit.unchangedSuffix
+                // Such hosts filter on `end == caret` themselves, so give them the changed span only
+                let cumulativeDiff = config.allowCaretBoundaryCorrections
+                    ? CorrectionDiff(
+                        start: activeRegion.start,
+                        end: activeRegion.end,
+                        text: finalRegionText,
+                        stage: primaryStage,
+                        confidence: confidence,
+                        safeToAutoApply: safeToAutoApply
+                    )
+                    : CorrectionDiff(
+                        start: activeRegion.start + edit.unchangedPrefix,
+                        end: changedEnd,
+                        text: edit.inserted,
+                        stage: primaryStage,
+                        confidence: confidence,
+                        safeToAutoApply: safeToAutoApply
+                    )
+                let correction = SpanCorrection(edit: edit, regionStart: activeRegion.start, in: text)
+                if config.allowCaretBoundaryCorrections || changedEnd != caret {
+                    attemptedCorrection = correction
+                    if !isSuppressedByChurn(correction, fieldKey: fieldKey) {
+                        finalDiffs.append(cumulativeDiff)
+                    }
+                }
             }
         }
         
-        return CorrectionWaveResult(
+        let result = CorrectionWaveResult(
             diffs: finalDiffs,
             activeRegion: activeRegion,
             durationMs: durationMs,
             stagesApplied: stagesApplied,
-            correctedText: currentText != text ? currentText : nil
+            correctedText: finalDiffs.isEmpty ? nil : currentText,
+            stageErrors: diagnostics.failures.map { StageError(stage: $0.stage, message: $0.error.localizedDescription) },
+            confidenceHistogram: config.includeConfidenceHistogram
+                ? confidenceHistogram(diagnostics.candidates.map(\.confidence))
+                : nil,
+            candidates: config.includeCandidates ? diagnostics.candidates : nil,
+            warnings: config.warnings
+        ).rebased(to: config.offsetBase)
+        
+        if let auditHandler {
+            result.diffs.forEach(auditHandler)
+        }
+        
+        recentLatencies.append(durationMs)
+        if recentLatencies.count > Self.latencyWindowSize {
+            recentLatencies.removeFirst(recentLatencies.count - Self.latencyWindowSize)
+        }
+        
+        touchField(fieldKey)
+        // A wave that skipped a failed stage is degraded; keep it for `lastResult` only so
+        // the next identical call (or one inside the rate-limit interval) retries the stage
+        let degraded = !diagnostics.failures.isEmpty
+        fieldStates[fieldKey]?.lastWave = (degraded ? nil : cacheKey, result)
+        fieldStates[fieldKey]?.lastComputedAt = degraded ? nil : Date()
+        recordChurn(attemptedCorrection, emitted: !finalDiffs.isEmpty, fieldKey: fieldKey)
+        recordHistory(text: text, result: result, fieldKey: fieldKey)
+        return result
+    }
+    
+    /// A wave with nothing to correct. It still becomes the field's last result so
+    /// `lastResult` never reports diffs for text that has moved on, but it is not cached
+    private func emptyWave(region: TextRegion, startTime: Date, fieldKey: String) -> CorrectionWaveResult {
+        let result = CorrectionWaveResult(
+            diffs: [],
+            activeRegion: region,
+            durationMs: Date().timeIntervalSince(startTime) * 1000,
+            warnings: config.warnings
+        ).rebased(to: config.offsetBase)
+        touchField(fieldKey)
+        fieldStates[fieldKey]?.lastWave = (nil, result)
+        fieldStates[fieldKey]?.lastComputedAt = nil
+        return result
+    }
+    
+    // MARK: - Replay
+    
+    /// A previous result carried over to newer `text`: its diffs are kept only while the spans
+    /// they replace still read the same and remain caret-safe, and `correctedText` is rebuilt
+    /// from `text` so applying it never drops what was typed since
+    private func replay(
+        _ result: CorrectionWaveResult,
+        computedFor oldText: String,
+        onto text: String,
+        caret: Int
+    ) -> CorrectionWaveResult {
+        let documentResult = result.rebased(to: .document)
+        let spansUnchanged = documentResult.diffs.allSatisfy { diff in
+            let span = TextRegion(start: diff.start, end: diff.end)
+            return diff.end <= text.count && extractSpan(from: oldText, region: span) == extractSpan(from: text, region: span)
+        }
+        let correctedText = spansUnchanged && !documentResult.diffs.isEmpty
+            ? applyDiffs(text: text, diffs: documentResult.diffs, caret: caret)?.text
+            : nil
+        
+        return CorrectionWaveResult(
+            diffs: correctedText == nil ? [] : documentResult.diffs,
+            activeRegion: documentResult.activeRegion,
+            durationMs: documentResult.durationMs,
+            stagesApplied: documentResult.stagesApplied,
+            correctedText: correctedText,
+            offsetBase: .document,
+            regionOrigin: documentResult.regionOrigin,
+            stageErrors: documentResult.stageErrors,
+            cached: documentResult.cached,
+            rateLimited: documentResult.rateLimited,
+            confidenceHistogram: documentResult.confidenceHistogram,
+            candidates: documentResult.candidates,
+            warnings: documentResult.warnings
+        ).rebased(to: config.offsetBase)
+    }
+    
+    // MARK: - Confidence Threshold
+    
+    /// Change the threshold at runtime (e.g. from a settings slider), clamped to 0.5...1.0
+    /// like `PipelineConfiguration.confidenceThreshold`
+    /// - Returns: The threshold actually applied, which differs from `threshold` when clamped
+    @discardableResult
+    public func setConfidenceThreshold(_ threshold: Double) -> Double {
+        confidenceThreshold = max(0.5, min(1.0, threshold))
+        // Cached waves were filtered against the old threshold
+        invalidateAllCaches()
+        return confidenceThreshold
+    }
+    
+    // MARK: - Confidence Scoring
+    
+    /// Score `stage`'s candidates with `scorer` instead of the built-in confidence; nil restores it.
+    /// Results are clamped to 0...1; NaN and infinite scores count as 0.
+    public func setConfidenceScorer(_ scorer: ConfidenceScorer?, for stage: CorrectionStage) {
+        confidenceScorers[stage] = scorer
+        // Cached waves were scored the old way
+        invalidateAllCaches()
+    }
+    
+    // MARK: - Audit
+    
+    /// Observe every correction the pipeline suggests, accepted by the host or not, for
+    /// audit trails. Called once per diff of each computed wave, before it is returned;
+    /// cached and rate-limited replays aren't reported again. nil disables.
+    public func setAuditHandler(_ handler: AuditHandler?) {
+        auditHandler = handler
+    }
+    
+    // MARK: - Auto-Apply
+    
+    /// The decision behind `CorrectionDiff.safeToAutoApply` under this pipeline's policy,
+    /// so hosts can pick UI treatment without re-deriving the rules
+    public nonisolated func wouldAutoApply(
+        stage: CorrectionStage,
+        category: CorrectionCategory? = nil,
+        confidence: Double
+    ) -> Bool {
+        config.autoApplyPolicy.allows(
+            stage: stage,
+            category: category ?? stage.defaultCategory,
+            confidence: confidence
         )
     }
     
+    // MARK: - Last Result
+    
+    /// The most recent result computed for a field, for re-showing suggestions without
+    /// rerunning the LM; nil if the field has no wave yet (or was evicted)
+    public func lastResult(fieldId: String? = nil) -> CorrectionWaveResult? {
+        fieldStates[fieldId ?? Self.defaultFieldID]?.lastWave?.result
+    }
+    
+    // MARK: - Churn
+    
+    /// Spans in a field whose correction has flipped back and forth, most flips first.
+    /// A span is forgotten after 20 computed waves with no correction there.
+    public func churn(fieldId: String? = nil) -> [SpanChurn] {
+        (fieldStates[fieldId ?? Self.defaultFieldID]?.churn ?? [])
+            .filter { $0.flips > 0 }
+            .sorted { $0.flips > $1.flips }
+            .map { record in
+                let start = record.correction.start
+                return SpanChurn(
+                    region: TextRegion(start: start, end: start + record.correction.edit.inserted.count),
+                    flips: record.flips
+                )
+            }
+    }
+    
+    /// Whether `correction` undoes a span's previous one after that span already flipped
+    /// `config.churnSuppressionLimit` times
+    private func isSuppressedByChurn(_ correction: SpanCorrection, fieldKey: String) -> Bool {
+        guard let limit = config.churnSuppressionLimit,
+              let record = fieldStates[fieldKey]?.churn.first(where: { $0.correction.isUndone(by: correction) }) else {
+            return false
+        }
+        return record.flips >= limit
+    }
+    
+    /// Count a wave toward the churn window and note the correction it made (or tried to make)
+    private func recordChurn(_ correction: SpanCorrection?, emitted: Bool, fieldKey: String) {
+        guard var state = fieldStates[fieldKey] else { return }
+        state.waveCount += 1
+        if let correction {
+            if let index = state.churn.firstIndex(where: { $0.correction.isUndone(by: correction) }) {
+                if emitted {
+                    state.churn[index].correction = correction
+                    state.churn[index].flips += 1
+                }
+                // A suppressed flip keeps the span under watch
+                state.churn[index].lastWave = state.waveCount
+            } else if emitted {
+                state.churn.removeAll { $0.correction.start == correction.start }
+                state.churn.append(ChurnRecord(correction: correction, lastWave: state.waveCount))
+            }
+        }
+        let now = state.waveCount
+        state.churn.removeAll { now - $0.lastWave >= Self.churnWindowWaves }
+        fieldStates[fieldKey] = state
+    }
+    
+    // MARK: - Latency
+    
+    /// Percentiles over the last 100 computed waves (cached and rate-limited replays excluded);
+    /// nil before the first one
+    public func latencyStats() -> LatencyStats? {
+        LatencyStats(samples: recentLatencies)
+    }
+    
+    // MARK: - Cache Invalidation
+    
+    /// Force the next wave for a field to recompute, e.g. after a programmatic edit
+    /// the cache key can't see. History and `lastResult` are kept.
+    public func invalidateCache(fieldId: String? = nil) {
+        let fieldKey = fieldId ?? Self.defaultFieldID
+        fieldStates[fieldKey]?.lastWave?.key = nil
+        fieldStates[fieldKey]?.lastComputedAt = nil
+        // Offsets it tracked may no longer point at the same text
+        fieldStates[fieldKey]?.churn.removeAll()
+    }
+    
+    /// Force the next wave for every field to recompute
+    public func invalidateAllCaches() {
+        for fieldKey in fieldStates.keys {
+            invalidateCache(fieldId: fieldKey)
+        }
+    }
+    
+    // MARK: - History
+    
+    /// The most recent correction sets this pipeline returned for a field, newest first
+    public func history(max: Int = .max, fieldId: String? = nil) -> [CorrectionHistoryEntry] {
+        Array((fieldStates[fieldId ?? Self.defaultFieldID]?.history ?? []).prefix(Swift.max(0, max)))
+    }
+    
+    /// Drop all recorded history for a field
+    public func clearHistory(fieldId: String? = nil) {
+        fieldStates[fieldId ?? Self.defaultFieldID]?.history.removeAll()
+    }
+    
+    private func recordHistory(text: String, result: CorrectionWaveResult, fieldKey: String) {
+        guard config.historyLimit > 0, !result.diffs.isEmpty else { return }
+        
+        fieldStates[fieldKey]?.history.insert(
+            CorrectionHistoryEntry(timestamp: Date(), originalText: text, result: result),
+            at: 0
+        )
+        if let count = fieldStates[fieldKey]?.history.count, count > config.historyLimit {
+            fieldStates[fieldKey]?.history.removeLast(count - config.historyLimit)
+        }
+    }
+    
+    // MARK: - Field State
+    
+    /// Mark a field most recently used, creating its state and evicting beyond capacity
+    private func touchField(_ fieldKey: String) {
+        if fieldStates[fieldKey] == nil {
+            fieldStates[fieldKey] = FieldState()
+        }
+        fieldRecency.removeAll { $0 == fieldKey }
+        fieldRecency.append(fieldKey)
+        
+        while fieldRecency.count > config.fieldStateCapacity {
+            fieldStates.removeValue(forKey: fieldRecency.removeFirst())
+        }
+    }
+    
+    // MARK: - Stage Isolation
+    
+    /// Run a stage so its failure skips only that stage instead of the whole wave,
+    /// recording every candidate it proposes before threshold filtering
+    private func isolated(
+        _ stage: CorrectionStage,
+        recording diagnostics: inout WaveDiagnostics,
+        _ operation: () async throws -> CorrectionDiff?
+    ) async throws -> CorrectionDiff? {
+        do {
+            let candidate = try await operation()
+            if let candidate {
+                diagnostics.candidates.append(candidate)
+            }
+            return candidate
+        } catch is CancellationError {
+            throw CancellationError()
+        } catch {
+            diagnostics.failures.append((stage, error))
+            return nil
+        }
+    }
+    
     // MARK: - Stage Implementations
     
     private func runNoiseStage(
@@ -252,12 +664,18 @@ public actor CorrectionPipeline {
             return nil
         }
         
+        // A non-finite score (NaN, ±infinity) would clamp unpredictably, so it counts as no confidence
+        let confidence = confidenceScorers[stage].map { scorer -> Double in
+            let score = scorer(originalSpan, replacement)
+            return score.isFinite ? max(0, min(1, score)) : 0
+        } ?? 0.9  // TODO: Implement proper confidence scoring
+        
         return CorrectionDiff(
             start: region.start,
             end: region.end,
             text: replacement,
             stage: stage,
-            confidence: 0.9  // TODO: Implement proper confidence scoring
+            confidence: confidence
         )
     }
     
@@ -291,6 +709,137 @@ public actor CorrectionPipeline {
     }
 }
 
+// MARK: - Wave Diagnostics
+
+/// Per-wave bookkeeping of stage outcomes
+private struct WaveDiagnostics {
+    var failures: [(stage: CorrectionStage, error: Error)] = []
+    var candidates: [CorrectionDiff] = []
+}
+
+/// Count confidences into equal-width buckets over [0, 1]; 1.0 lands in the last bucket
+public func confidenceHistogram(_ confidences: [Double], buckets: Int = 10) -> [Int] {
+    guard buckets > 0 else { return [] }
+    var counts = Array(repeating: 0, count: buckets)
+    for confidence in confidences {
+        let index = Int(max(0, min(1, confidence)) * Double(buckets))
+        counts[min(index, buckets - 1)] += 1
+    }
+    return counts
+}
+
+/// The span a wave actually changed inside its region, with shared leading and trailing
+/// Characters stripped
+private struct RegionEdit: Equatable {
+    let removed: String
+    let inserted: String
+    /// Characters before the changed span that both versions share
+    var unchangedPrefix = 0
+    /// Characters after the changed span that both versions share
+    var unchangedSuffix = 0
+    
+    init(removed: String, inserted: String) {
+        self.removed = removed
+        self.inserted = inserted
+    }
+    
+    init(from original: String, to replacement: String) {
+        let a = Array(original), b = Array(replacement)
+        let limit = min(a.count, b.count)
+        var prefix = 0
+        while prefix < limit, a[prefix] == b[prefix] {
+            prefix += 1
+        }
+        var suffix = 0
+        while suffix < limit - prefix, a[a.count - 1 - suffix] == b[b.count - 1 - suffix] {
+            suffix += 1
+        }
+        self.init(
+            removed: String(a[prefix..<(a.count - suffix)]),
+            inserted: String(b[prefix..<(b.count - suffix)])
+        )
+        self.unchangedPrefix = prefix
+        self.unchangedSuffix = suffix
+    }
+    
+    /// The edit that would undo this one
+    var reversed: RegionEdit {
+        RegionEdit(removed: inserted, inserted: removed)
+    }
+    
+    static func == (lhs: RegionEdit, rhs: RegionEdit) -> Bool {
+        lhs.removed == rhs.removed && lhs.inserted == rhs.inserted
+    }
+}
+
+/// An emitted edit pinned to where it happened: its document offset and the text around it
+private struct SpanCorrection {
+    /// How much surrounding text identifies the spot
+    private static let contextLength = 16
+    
+    let start: Int
+    let edit: RegionEdit
+    let leading: String
+    let trailing: String
+    
+    /// - Parameter text: The document the edit applies to, with `regionStart` in document offsets
+    init(edit: RegionEdit, regionStart: Int, in text: String) {
+        let start = regionStart + edit.unchangedPrefix
+        let characters = Array(text)
+        let leadingStart = max(0, start - Self.contextLength)
+        let trailingStart = min(characters.count, start + edit.removed.count)
+        self.start = start
+        self.edit = edit
+        self.leading = String(characters[leadingStart..<start])
+        self.trailing = String(characters[trailingStart..<min(characters.count, trailingStart + Self.contextLength)])
+    }
+    
+    /// Whether `later` puts back exactly what this correction replaced, at the same spot.
+    /// Trailing context only has to agree where both have it, since typing extends it.
+    func isUndone(by later: SpanCorrection) -> Bool {
+        later.start == start
+            && later.edit == edit.reversed
+            && later.leading == leading
+            && (later.trailing.hasPrefix(trailing) || trailing.hasPrefix(later.trailing))
+    }
+}
+
+/// A span's latest correction and how many times corrections there have undone each other
+private struct ChurnRecord {
+    var correction: SpanCorrection
+    var flips = 0
+    /// `FieldState.waveCount` when this span last saw a correction
+    var lastWave: Int
+}
+
+// MARK: - Field State
+
+/// Everything the pipeline remembers about one editor field
+private struct FieldState {
+    /// The last computed wave, reused while its inputs are unchanged (nil key = invalidated)
+    var lastWave: (key: WaveCacheKey?, result: CorrectionWaveResult)?
+    /// When `lastWave` was actually computed, for rate limiting
+    var lastComputedAt: Date?
+    /// Recent corrections, newest first (only kept when `config.historyLimit > 0`)
+    var history: [CorrectionHistoryEntry] = []
+    /// Computed waves so far, the clock for `churn`
+    var waveCount = 0
+    /// Recently corrected spans and how often each flipped; cleared by invalidation
+    var churn: [ChurnRecord] = []
+}
+
+// MARK: - Wave Cache
+
+/// The per-call inputs a wave's result depends on. The whole text is included because
+/// stage prompts read context around the region and `correctedText` is the full document.
+/// Runtime threshold and scorer changes invalidate cached waves explicitly.
+private struct WaveCacheKey: Equatable {
+    let region: TextRegion
+    let text: String
+    let caret: Int
+    let toneTarget: ToneTarget
+}
+
 // MARK: - Convenience Extension
 
 extension CorrectionPipeline {
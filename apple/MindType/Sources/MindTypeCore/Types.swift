@@ -36,6 +36,44 @@ public struct TextRegion: Equatable, Codable, Sendable {
             end: min(end, length)
         )
     }
+    
+    /// The smallest region containing all of `regions` (e.g. to send several flagged
+    /// spans to the LM at once), or nil if there are none
+    public static func covering(_ regions: [TextRegion]) -> TextRegion? {
+        guard let start = regions.map(\.start).min(), let end = regions.map(\.end).max() else {
+            return nil
+        }
+        return TextRegion(start: start, end: end)
+    }
+}
+
+/// A region's bounds in each index space hosts commonly use
+public struct RegionOffsets: Equatable, Sendable {
+    /// Character (grapheme cluster) offsets, as used throughout MindTypeCore
+    public let characters: Range<Int>
+    /// UTF-8 byte offsets
+    public let utf8: Range<Int>
+    /// UTF-16 code unit offsets (NSString / NSRange)
+    public let utf16: Range<Int>
+}
+
+extension TextRegion {
+    /// This region's bounds within `text` in every index space
+    /// - Returns: nil if the region extends past the end of `text`
+    public func offsets(in text: String) -> RegionOffsets? {
+        guard end <= text.count else { return nil }
+        
+        let lower = text.index(text.startIndex, offsetBy: start)
+        let upper = text.index(lower, offsetBy: length)
+        
+        return RegionOffsets(
+            characters: start..<end,
+            utf8: text.utf8.distance(from: text.startIndex, to: lower)
+                ..< text.utf8.distance(from: text.startIndex, to: upper),
+            utf16: text.utf16.distance(from: text.startIndex, to: lower)
+                ..< text.utf16.distance(from: text.startIndex, to: upper)
+        )
+    }
 }
 
 // MARK: - Correction Diff
@@ -47,19 +85,113 @@ public struct CorrectionDiff: Equatable, Codable, Sendable {
     public let text: String
     public let stage: CorrectionStage
     public let confidence: Double
+    /// Semantic grouping for host filtering, independent of `stage`
+    public let category: CorrectionCategory
+    /// Whether hosts may apply this silently rather than surfacing it as a suggestion
+    public let safeToAutoApply: Bool
     
-    public init(start: Int, end: Int, text: String, stage: CorrectionStage, confidence: Double = 1.0) {
+    /// - Parameter safeToAutoApply: Defaults to `AutoApplyPolicy.default`'s decision
+    public init(
+        start: Int,
+        end: Int,
+        text: String,
+        stage: CorrectionStage,
+        confidence: Double = 1.0,
+        category: CorrectionCategory? = nil,
+        safeToAutoApply: Bool? = nil
+    ) {
+        let resolvedCategory = category ?? stage.defaultCategory
         self.start = start
         self.end = end
         self.text = text
         self.stage = stage
         self.confidence = confidence
+        self.category = resolvedCategory
+        self.safeToAutoApply = safeToAutoApply ?? AutoApplyPolicy.default.allows(
+            stage: stage,
+            category: resolvedCategory,
+            confidence: confidence
+        )
+    }
+    
+    private enum CodingKeys: String, CodingKey {
+        case start, end, text, stage, confidence, category, safeToAutoApply
+    }
+    
+    /// Diffs encoded before `category` and `safeToAutoApply` existed decode with the
+    /// same defaults the memberwise init uses
+    public init(from decoder: Decoder) throws {
+        let container = try decoder.container(keyedBy: CodingKeys.self)
+        try self.init(
+            start: container.decode(Int.self, forKey: .start),
+            end: container.decode(Int.self, forKey: .end),
+            text: container.decode(String.self, forKey: .text),
+            stage: container.decode(CorrectionStage.self, forKey: .stage),
+            confidence: container.decode(Double.self, forKey: .confidence),
+            category: container.decodeIfPresent(CorrectionCategory.self, forKey: .category),
+            safeToAutoApply: container.decodeIfPresent(Bool.self, forKey: .safeToAutoApply)
+        )
     }
     
     /// Length change when this diff is applied
     public var lengthDelta: Int {
         text.count - (end - start)
     }
+    
+    /// Writing direction of the replacement, so hosts can place underlines in RTL runs.
+    /// Offsets stay in logical order either way.
+    public var direction: TextDirection {
+        TextDirection(of: text)
+    }
+    
+    /// The same diff with its offsets moved by `offset`
+    public func shifted(by offset: Int) -> CorrectionDiff {
+        CorrectionDiff(
+            start: start + offset,
+            end: end + offset,
+            text: text,
+            stage: stage,
+            confidence: confidence,
+            category: category,
+            safeToAutoApply: safeToAutoApply
+        )
+    }
+}
+
+// MARK: - Auto-Apply Policy
+
+/// Decides which corrections are safe to apply silently (obvious typos, spacing)
+/// and which need the user's confirmation (grammar, tone)
+public struct AutoApplyPolicy: Equatable, Sendable {
+    /// Categories eligible for silent application
+    public let safeCategories: Set<CorrectionCategory>
+    /// Minimum confidence for silent application
+    public let minimumConfidence: Double
+    
+    public init(
+        safeCategories: Set<CorrectionCategory> = [.spelling, .punctuation, .capitalization, .typography],
+        minimumConfidence: Double = 0.9
+    ) {
+        self.safeCategories = safeCategories
+        self.minimumConfidence = minimumConfidence
+    }
+    
+    public static let `default` = AutoApplyPolicy()
+    
+    /// Whether a correction with these properties may be applied without confirmation
+    public func allows(stage: CorrectionStage, category: CorrectionCategory, confidence: Double) -> Bool {
+        stage != .tone && safeCategories.contains(category) && confidence >= minimumConfidence
+    }
+}
+
+// MARK: - Offset Base
+
+/// Offset space used for reported diffs and regions
+public enum OffsetBase: String, Codable, CaseIterable, Sendable {
+    /// Offsets are absolute within the full document
+    case document
+    /// Offsets are relative to the start of the active region
+    case region
 }
 
 // MARK: - Correction Stage
@@ -77,6 +209,51 @@ public enum CorrectionStage: String, Codable, CaseIterable, Sendable {
         case .tone: return "Tone"
         }
     }
+    
+    /// Category reported when a stage doesn't tag its diff explicitly
+    public var defaultCategory: CorrectionCategory {
+        switch self {
+        case .noise: return .spelling
+        case .context: return .grammar
+        case .tone: return .tone
+        }
+    }
+}
+
+// MARK: - Correction Category
+
+/// Semantic correction categories hosts can toggle in settings
+public enum CorrectionCategory: String, Codable, CaseIterable, Sendable {
+    case spelling = "spelling"
+    case grammar = "grammar"
+    case punctuation = "punctuation"
+    case capitalization = "capitalization"
+    case typography = "typography"
+    case tone = "tone"
+}
+
+// MARK: - Text Direction
+
+/// Writing direction of a span, from its first strongly directional letter
+public enum TextDirection: String, Codable, CaseIterable, Sendable {
+    case ltr
+    case rtl
+    
+    /// Text with no letters (digits, punctuation) reads as `.ltr`
+    public init(of text: String) {
+        let firstLetter = text.unicodeScalars.first { $0.properties.isAlphabetic }
+        self = firstLetter.map(Self.isRightToLeft) == true ? .rtl : .ltr
+    }
+    
+    /// Hebrew, Arabic, Syriac, Thaana, NKo and related blocks, plus their presentation forms
+    private static func isRightToLeft(_ scalar: Unicode.Scalar) -> Bool {
+        switch scalar.value {
+        case 0x0590...0x08FF, 0xFB1D...0xFDFF, 0xFE70...0xFEFF, 0x10800...0x10FFF, 0x1E800...0x1EFFF:
+            return true
+        default:
+            return false
+        }
+    }
 }
 
 // MARK: - Tone Target
@@ -86,6 +263,19 @@ public enum ToneTarget: String, Codable, CaseIterable, Sendable {
     case none = "None"
     case casual = "Casual"
     case professional = "Professional"
+    
+    /// Parse host-supplied tone names leniently: "Casual", "casual " and "CASUAL" all match.
+    /// Returns nil for names that aren't a supported tone.
+    public init?(normalizing name: String) {
+        let key = name
+            .trimmingCharacters(in: .whitespacesAndNewlines)
+            .precomposedStringWithCanonicalMapping
+            .lowercased()
+        guard let tone = ToneTarget.allCases.first(where: { $0.rawValue.lowercased() == key }) else {
+            return nil
+        }
+        self = tone
+    }
 }
 
 // MARK: - Correction Wave
@@ -102,19 +292,153 @@ public struct CorrectionWaveResult: Sendable {
     public let stagesApplied: [CorrectionStage]
     /// The final corrected text (convenience)
     public let correctedText: String?
+    /// Offset space of `diffs` and `activeRegion`
+    public let offsetBase: OffsetBase
+    /// Document offset of the active region start, kept so results can be rebased
+    public let regionOrigin: Int
+    /// Stages that failed and were skipped while the rest of the wave continued
+    public let stageErrors: [StageError]
+    /// True when this result was reused from the previous wave without running the LM
+    public internal(set) var cached: Bool = false
+    /// True when the call arrived within `minProcessIntervalMs` and got the previous result
+    public internal(set) var rateLimited: Bool = false
+    /// True when the caller's `baseText` differs from the live text inside the active region,
+    /// so the diffs no longer line up with what the host would apply them to
+    public internal(set) var stale: Bool = false
+    /// Ten-bucket distribution of all stage candidates' confidences, accepted or not
+    /// (only when `includeConfidenceHistogram` is set)
+    public let confidenceHistogram: [Int]?
+    /// Every stage proposal before threshold and caret-boundary filtering, for acceptance
+    /// metrics (only when `includeCandidates` is set). Each is in the offsets of the text
+    /// its stage saw, which is the original text shifted by earlier stages' edits.
+    public let candidates: [CorrectionDiff]?
+    /// Configuration values that were out of range and clamped (`PipelineConfiguration.warnings`)
+    public let warnings: [String]
     
     public init(
         diffs: [CorrectionDiff],
         activeRegion: TextRegion,
         durationMs: Double,
         stagesApplied: [CorrectionStage] = [],
-        correctedText: String? = nil
+        correctedText: String? = nil,
+        offsetBase: OffsetBase = .document,
+        regionOrigin: Int? = nil,
+        stageErrors: [StageError] = [],
+        cached: Bool = false,
+        rateLimited: Bool = false,
+        confidenceHistogram: [Int]? = nil,
+        candidates: [CorrectionDiff]? = nil,
+        warnings: [String] = []
     ) {
         self.diffs = diffs
         self.activeRegion = activeRegion
         self.durationMs = durationMs
         self.stagesApplied = stagesApplied
         self.correctedText = correctedText
+        self.offsetBase = offsetBase
+        self.regionOrigin = regionOrigin ?? (offsetBase == .document ? activeRegion.start : 0)
+        self.stageErrors = stageErrors
+        self.cached = cached
+        self.rateLimited = rateLimited
+        self.confidenceHistogram = confidenceHistogram
+        self.candidates = candidates
+        self.warnings = warnings
+    }
+    
+    /// The same result with diffs and region expressed in `base`
+    public func rebased(to base: OffsetBase) -> CorrectionWaveResult {
+        guard base != offsetBase else { return self }
+        let offset = base == .region ? -regionOrigin : regionOrigin
+        return CorrectionWaveResult(
+            diffs: diffs.map { $0.shifted(by: offset) },
+            activeRegion: TextRegion(start: activeRegion.start + offset, end: activeRegion.end + offset),
+            durationMs: durationMs,
+            stagesApplied: stagesApplied,
+            correctedText: correctedText,
+            offsetBase: base,
+            regionOrigin: regionOrigin,
+            stageErrors: stageErrors,
+            cached: cached,
+            rateLimited: rateLimited,
+            confidenceHistogram: confidenceHistogram,
+            candidates: candidates?.map { $0.shifted(by: offset) },
+            warnings: warnings
+        )
+    }
+    
+    /// Apply the diffs to `text`, which must be in this result's offset space
+    /// (the full document for `.document`, the region text for `.region`)
+    public func applied(to text: String) -> String? {
+        applyDiffs(text: text, diffs: diffs, caret: activeRegion.end)?.text
+    }
+}
+
+// MARK: - Correction History
+
+/// A correction set the pipeline returned, kept for "undo last auto-correction" UX
+public struct CorrectionHistoryEntry: Sendable {
+    public let timestamp: Date
+    /// The text the corrections were computed against
+    public let originalText: String
+    public let result: CorrectionWaveResult
+    
+    public init(timestamp: Date, originalText: String, result: CorrectionWaveResult) {
+        self.timestamp = timestamp
+        self.originalText = originalText
+        self.result = result
+    }
+}
+
+// MARK: - Stage Error
+
+/// A stage that failed during a correction wave
+public struct StageError: Equatable, Codable, Sendable {
+    public let stage: CorrectionStage
+    public let message: String
+    
+    public init(stage: CorrectionStage, message: String) {
+        self.stage = stage
+        self.message = message
+    }
+}
+
+// MARK: - Latency Stats
+
+/// Nearest-rank latency percentiles over recently computed waves, for regression monitoring
+public struct LatencyStats: Equatable, Codable, Sendable {
+    public let count: Int
+    public let p50: Double
+    public let p95: Double
+    public let p99: Double
+    
+    /// nil when there are no samples
+    public init?(samples: [Double]) {
+        guard !samples.isEmpty else { return nil }
+        let sorted = samples.sorted()
+        func percentile(_ p: Double) -> Double {
+            let rank = Int((p / 100 * Double(sorted.count)).rounded(.up))
+            return sorted[max(0, rank - 1)]
+        }
+        self.count = sorted.count
+        self.p50 = percentile(50)
+        self.p95 = percentile(95)
+        self.p99 = percentile(99)
+    }
+}
+
+// MARK: - Span Churn
+
+/// How often a span's correction has flipped back and forth in one field, e.g. when stages
+/// or an unstable model undo each other between waves (encode as JSON for diagnostics)
+public struct SpanChurn: Equatable, Codable, Sendable {
+    /// Document offsets of the span as its latest correction left it
+    public let region: TextRegion
+    /// Corrections that undid the one before them at this span
+    public let flips: Int
+    
+    public init(region: TextRegion, flips: Int) {
+        self.region = region
+        self.flips = flips
     }
 }
 
@@ -179,22 +503,111 @@ public struct PipelineConfiguration: Sendable {
     public let toneTarget: ToneTarget
     /// LLM temperature for generation creativity (0.0-1.0, lower = more deterministic)
     public let temperature: Float
+    /// Line breaks the active region never crosses
+    public let paragraphBoundary: ParagraphBoundary
+    /// Whether waves cover the trailing words or only the word just finished
+    public let regionScope: RegionScope
+    /// Extra word-splitting characters for region word counting (nil = Unicode word boundaries)
+    public let wordSeparators: Set<Character>?
+    /// Start the region inside an open quote/parenthetical around the caret
+    public let anchorToEnclosure: Bool
+    /// Whether results report document-absolute or region-relative offsets
+    public let offsetBase: OffsetBase
+    /// Number of returned correction sets the pipeline remembers (0 = off)
+    public let historyLimit: Int
+    /// Attach a confidence histogram of all candidates to each result, for threshold tuning
+    public let includeConfidenceHistogram: Bool
+    /// Attach every stage candidate, accepted or not, to each result
+    public let includeCandidates: Bool
+    /// Number of editor fields whose cache/history is kept before evicting the oldest
+    public let fieldStateCapacity: Int
+    /// Calls sooner than this after the last real computation reuse its result (0 = off)
+    public let minProcessIntervalMs: Int
+    /// Whether a correction may end exactly at the caret. false suppresses it and trims each
+    /// diff to the characters it changes, so `end` is where the edit really stops.
+    public let allowCaretBoundaryCorrections: Bool
+    /// Decides each diff's `safeToAutoApply` flag
+    public let autoApplyPolicy: AutoApplyPolicy
+    /// Drop a correction that would flip a span back once it has already flipped this many
+    /// times recently (0 = never undo a correction; nil = no suppression, only track churn)
+    public let churnSuppressionLimit: Int?
+    /// One message per value that was out of range and clamped, so host bugs surface
+    public let warnings: [String]
     
     public init(
         activeRegionWords: Int = 20,
         confidenceThreshold: Double = 0.80,
         toneTarget: ToneTarget = .none,
-        temperature: Float = 0.1
+        temperature: Float = 0.1,
+        paragraphBoundary: ParagraphBoundary = .blankLine,
+        regionScope: RegionScope = .trailingWords,
+        wordSeparators: Set<Character>? = nil,
+        anchorToEnclosure: Bool = false,
+        offsetBase: OffsetBase = .document,
+        historyLimit: Int = 0,
+        includeConfidenceHistogram: Bool = false,
+        includeCandidates: Bool = false,
+        fieldStateCapacity: Int = 16,
+        minProcessIntervalMs: Int = 0,
+        allowCaretBoundaryCorrections: Bool = true,
+        autoApplyPolicy: AutoApplyPolicy = .default,
+        churnSuppressionLimit: Int? = nil
     ) {
-        self.activeRegionWords = max(5, min(50, activeRegionWords))
-        self.confidenceThreshold = max(0.5, min(1.0, confidenceThreshold))
+        var warnings: [String] = []
+        func clamped<Value: Comparable>(_ name: String, _ value: Value, to range: ClosedRange<Value>) -> Value {
+            let result = max(range.lowerBound, min(range.upperBound, value))
+            if result != value {
+                warnings.append("\(name) \(value) is outside \(range.lowerBound)...\(range.upperBound); clamped to \(result)")
+            }
+            return result
+        }
+        
+        self.activeRegionWords = clamped("activeRegionWords", activeRegionWords, to: 5...50)
+        self.confidenceThreshold = clamped("confidenceThreshold", confidenceThreshold, to: 0.5...1.0)
         self.toneTarget = toneTarget
-        self.temperature = max(0.0, min(1.0, temperature))
+        self.temperature = clamped("temperature", temperature, to: 0.0...1.0)
+        self.paragraphBoundary = paragraphBoundary
+        self.regionScope = regionScope
+        self.wordSeparators = wordSeparators
+        self.anchorToEnclosure = anchorToEnclosure
+        self.offsetBase = offsetBase
+        self.historyLimit = max(0, historyLimit)
+        self.includeConfidenceHistogram = includeConfidenceHistogram
+        self.includeCandidates = includeCandidates
+        self.fieldStateCapacity = max(1, fieldStateCapacity)
+        self.minProcessIntervalMs = max(0, minProcessIntervalMs)
+        self.allowCaretBoundaryCorrections = allowCaretBoundaryCorrections
+        self.autoApplyPolicy = autoApplyPolicy
+        self.churnSuppressionLimit = churnSuppressionLimit.map { max(0, $0) }
+        self.warnings = warnings
     }
     
     public static var `default`: PipelineConfiguration {
         PipelineConfiguration()
     }
+    
+    /// The stages a wave runs under this configuration, in execution order,
+    /// for settings UIs that render per-stage toggles
+    public var stages: [StageDescriptor] {
+        CorrectionStage.allCases.enumerated().map { order, stage in
+            StageDescriptor(
+                stage: stage,
+                category: stage.defaultCategory,
+                enabled: stage != .tone || toneTarget != .none,
+                order: order
+            )
+        }
+    }
+}
+
+// MARK: - Stage Descriptor
+
+/// A pipeline stage as configured: what it is, whether it runs, and when
+public struct StageDescriptor: Equatable, Codable, Sendable {
+    public let stage: CorrectionStage
+    public let category: CorrectionCategory
+    public let enabled: Bool
+    public let order: Int
 }
 
 // MARK: - Marker State (Caret Organism)
@@ -72,6 +72,9 @@ public final class TypingMonitor: ObservableObject {
     private var pauseTimer: Timer?
     private var currentSweep: SweepState?
     private var sweepDisplayLink: CADisplayLink?
+    /// Bumped when state is replaced wholesale; a correction started under an older
+    /// generation drops its result instead of writing over the new state
+    private var generation = 0
     
     // MARK: - Initialization
     
@@ -174,12 +177,83 @@ public final class TypingMonitor: ObservableObject {
         }
     }
     
+    /// Active region for the latest buffer and caret, for highlight-only hosts
+    /// that don't want to re-send the text
+    /// - Parameter policy: Pass `CorrectionPipeline.activeRegionPolicy` so the highlighted
+    ///   band matches the region the pipeline corrects
+    public func activeRegion(using policy: ActiveRegionPolicy) -> TextRegion {
+        policy.computeRegion(text: buffer, caret: caretPosition)
+    }
+    
     /// Force a correction now (Enter key or explicit request)
     public func forceCorrection() async {
         guard isEnabled, !buffer.isEmpty else { return }
         await triggerCorrection()
     }
     
+    // MARK: - Snapshot
+    
+    /// Serializable monitor state for crash recovery and deterministic replay.
+    /// Timers aren't captured; an interrupted burst re-arms pause detection on restore.
+    public struct Snapshot: Equatable, Codable, Sendable {
+        public let buffer: String
+        public let caretPosition: Int
+        public let isFocused: Bool
+        public let isEnabled: Bool
+        public let isBursting: Bool
+        public let lastKeystrokeTime: Date
+        public let pauseThresholdMs: Int
+        public let minCharacters: Int
+        public let minWords: Int
+    }
+    
+    /// Capture the current state
+    public func snapshot() -> Snapshot {
+        Snapshot(
+            buffer: buffer,
+            caretPosition: caretPosition,
+            isFocused: markerState != .dormant,
+            isEnabled: isEnabled,
+            isBursting: rhythm.isBursting,
+            lastKeystrokeTime: lastKeystrokeTime,
+            pauseThresholdMs: pauseThresholdMs,
+            minCharacters: minCharacters,
+            minWords: minWords
+        )
+    }
+    
+    /// Replace the current state with a previously captured snapshot
+    /// An in-flight correction or sweep is abandoned
+    public func restore(from snapshot: Snapshot) {
+        pauseTimer?.invalidate()
+        pauseTimer = nil
+        currentSweep = nil
+        generation += 1
+        
+        buffer = snapshot.buffer
+        caretPosition = snapshot.caretPosition
+        isEnabled = snapshot.isEnabled
+        lastKeystrokeTime = snapshot.lastKeystrokeTime
+        pauseThresholdMs = snapshot.pauseThresholdMs
+        minCharacters = snapshot.minCharacters
+        minWords = snapshot.minWords
+        
+        if !snapshot.isFocused {
+            rhythm = .idle
+            markerState = .dormant
+        } else if !snapshot.isEnabled {
+            rhythm = .idle
+            markerState = .disabled
+        } else if snapshot.isBursting {
+            rhythm = .bursting(since: snapshot.lastKeystrokeTime)
+            markerState = .listening(position: caretPosition)
+            schedulePauseDetection()
+        } else {
+            rhythm = .idle
+            markerState = .idle(position: caretPosition)
+        }
+    }
+    
     // MARK: - Private Methods
     
     private func schedulePauseDetection() {
@@ -225,9 +299,12 @@ public final class TypingMonitor: ObservableObject {
         }
         
         rhythm = .correcting
+        let generation = self.generation
         
         // Request correction from pipeline
-        guard let result = await onPauseDetected?(buffer, caretPosition) else {
+        let result = await onPauseDetected?(buffer, caretPosition)
+        guard generation == self.generation else { return }
+        guard let result else {
             // No correction needed or error
             rhythm = .idle
             markerState = .idle(position: caretPosition)
@@ -236,19 +313,20 @@ public final class TypingMonitor: ObservableObject {
         
         // If corrections were made, start sweep animation
         if !result.diffs.isEmpty, let correctedText = result.correctedText {
-            await startSweep(result: result, correctedText: correctedText)
+            await startSweep(result: result, correctedText: correctedText, generation: generation)
         } else {
             // No changes
             markerState = .complete(position: caretPosition)
             
             // Brief pause then return to idle
             try? await Task.sleep(nanoseconds: 300_000_000) // 300ms
+            guard generation == self.generation else { return }
             rhythm = .idle
             markerState = .idle(position: caretPosition)
         }
     }
     
-    private func startSweep(result: CorrectionWaveResult, correctedText: String) async {
+    private func startSweep(result: CorrectionWaveResult, correctedText: String, generation: Int) async {
         let sweep = SweepState(
             startPosition: result.activeRegion.start,
             endPosition: caretPosition,
@@ -261,14 +339,20 @@ public final class TypingMonitor: ObservableObject {
         onSweepStart?(sweep)
         
         // Animate the sweep
-        await animateSweep(sweep: sweep, result: result, correctedText: correctedText)
+        await animateSweep(sweep: sweep, result: result, correctedText: correctedText, generation: generation)
     }
     
-    private func animateSweep(sweep: SweepState, result: CorrectionWaveResult, correctedText: String) async {
+    private func animateSweep(
+        sweep: SweepState,
+        result: CorrectionWaveResult,
+        correctedText: String,
+        generation: Int
+    ) async {
         let startTime = Date()
         let duration = sweep.duration
         
         while true {
+            guard generation == self.generation else { return }
             let elapsed = Date().timeIntervalSince(startTime)
             let progress = min(1.0, elapsed / duration)
             
@@ -308,6 +392,7 @@ public final class TypingMonitor: ObservableObject {
         
         // Brief pause then return to idle
         try? await Task.sleep(nanoseconds: 400_000_000) // 400ms
+        guard generation == self.generation else { return }
         
         currentSweep = nil
         rhythm = .idle
@@ -0,0 +1,238 @@
+/*╔══════════════════════════════════════════════════════════════╗
+  ║  ░  A C T I V E   R E G I O N   T E S T S  ░░░░░░░░░░░░░░░  ║
+  ╚══════════════════════════════════════════════════════════════╝
+*/
+
+import XCTest
+@testable import MindTypeCore
+
+final class ActiveRegionTests: XCTestCase {
+    
+    private func span(_ text: String, _ region: TextRegion) -> String {
+        let start = text.index(text.startIndex, offsetBy: region.start)
+        let end = text.index(text.startIndex, offsetBy: region.end)
+        return String(text[start..<end])
+    }
+    
+    func testComputeRegion_newlineBoundary_stopsAtPrecedingLine() {
+        let text = "first line\nsecond line"
+        let caret = "first line\nsecond li".count
+        let policy = ActiveRegionPolicy(paragraphBoundary: .newline)
+        
+        let region = policy.computeRegion(text: text, caret: caret)
+        
+        XCTAssertEqual(region, TextRegion(start: 11, end: caret))
+        XCTAssertFalse(span(text, region).contains("first line"))
+    }
+    
+    func testComputeRegion_blankLineBoundary_crossesSingleNewline() {
+        let text = "first line\nsecond line"
+        let caret = "first line\nsecond li".count
+        
+        let region = ActiveRegionPolicy(paragraphBoundary: .blankLine).computeRegion(text: text, caret: caret)
+        
+        XCTAssertEqual(region.start, 0)
+    }
+    
+    func testComputeRegion_blankLineBoundary_stopsAtParagraphBreak() {
+        let text = "first para\n\nsecond para"
+        let caret = text.count
+        
+        let region = ActiveRegionPolicy.default.computeRegion(text: text, caret: caret)
+        
+        XCTAssertEqual(span(text, region), "second para")
+    }
+    
+    func testComputeRegion_noBoundary_treatsNewlinesAsWhitespace() {
+        let text = "first para\n\nsecond para"
+        
+        let region = ActiveRegionPolicy(paragraphBoundary: .none).computeRegion(text: text, caret: text.count)
+        
+        XCTAssertEqual(region.start, 0)
+    }
+    
+    func testComputeRegion_lastWordScope_afterSeparator_coversOnlyThatWord() {
+        let text = "I saw teh "
+        let policy = ActiveRegionPolicy(scope: .lastWord)
+        
+        let region = policy.computeRegion(text: text, caret: text.count)
+        
+        XCTAssertEqual(span(text, region), "teh")
+    }
+    
+    func testComputeRegion_lastWordScope_midWord_isEmpty() {
+        let text = "I saw teh"
+        let policy = ActiveRegionPolicy(scope: .lastWord)
+        
+        XCTAssertTrue(policy.computeRegion(text: text, caret: 8).isEmpty)
+        XCTAssertTrue(policy.computeRegion(text: text, caret: text.count).isEmpty)
+    }
+    
+    func testExplainRegion_cutAtSentenceTerminator_reportsSentence() {
+        let text = "Hello there. one two three"
+        let explanation = ActiveRegionPolicy(targetWords: 4).explainRegion(text: text, caret: text.count)
+        
+        XCTAssertEqual(explanation.startReason, .sentence)
+        XCTAssertEqual(span(text, explanation.region), "one two three")
+    }
+    
+    func testExplainRegion_cutByLengthCap_reportsMaxLength() {
+        let text = "alpha beta gamma delta"
+        let explanation = ActiveRegionPolicy(maxCharacters: 10).explainRegion(text: text, caret: text.count)
+        
+        XCTAssertEqual(explanation.startReason, .maxLength)
+        XCTAssertEqual(explanation.region.length, 10)
+    }
+    
+    func testExplainRegion_reachesStartOfText_reportsBufferStart() {
+        let text = "short text"
+        let explanation = ActiveRegionPolicy.default.explainRegion(text: text, caret: text.count)
+        
+        XCTAssertEqual(explanation.startReason, .bufferStart)
+        XCTAssertEqual(explanation.region, ActiveRegionPolicy.default.computeRegion(text: text, caret: text.count))
+    }
+    
+    func testExplainRegion_stoppedByBlankLine_reportsParagraph() {
+        let text = "first para\n\nsecond para"
+        
+        XCTAssertEqual(ActiveRegionPolicy.default.explainRegion(text: text, caret: text.count).startReason, .paragraph)
+    }
+    
+    func testComputeRegion_slashSeparator_countsSlashedPairAsTwoWords() {
+        let text = "read/write access now"
+        let policy = ActiveRegionPolicy(targetWords: 3, wordSeparators: ["/"])
+        
+        XCTAssertEqual(span(text, policy.computeRegion(text: text, caret: text.count)), "write access now")
+    }
+    
+    func testComputeRegion_whitespaceOnlySeparators_countsSlashedPairAsOneWord() {
+        let text = "read/write access now"
+        let policy = ActiveRegionPolicy(targetWords: 3, wordSeparators: [])
+        
+        XCTAssertEqual(span(text, policy.computeRegion(text: text, caret: text.count)), text)
+    }
+    
+    func testComputeRegion_visualLines_neverCrossesPreviousLine() {
+        let text = "alpha beta gamma delta"
+        let policy = ActiveRegionPolicy.default
+        
+        let region = policy.computeRegion(text: text, caret: text.count, lineStarts: [0, 11])
+        
+        XCTAssertEqual(span(text, region), "gamma delta")
+    }
+    
+    func testComputeRegion_visualLines_caretOnFirstLine_usesFirstLine() {
+        let text = "alpha beta gamma delta"
+        
+        let region = ActiveRegionPolicy.default.computeRegion(text: text, caret: 10, lineStarts: [0, 11])
+        
+        XCTAssertEqual(span(text, region), "alpha beta")
+    }
+    
+    func testComputeRegion_anchorToEnclosure_caretInsideParenthetical_coversParentheticalOnly() {
+        let text = "I said (like this) to her"
+        let policy = ActiveRegionPolicy(anchorToEnclosure: true)
+        
+        let explanation = policy.explainRegion(text: text, caret: 17)
+        
+        XCTAssertEqual(span(text, explanation.region), "like this")
+        XCTAssertEqual(explanation.startReason, .enclosure)
+    }
+    
+    func testComputeRegion_anchorToEnclosure_closedParenthetical_usesWholeSentence() {
+        let text = "I said (like this) to her"
+        let policy = ActiveRegionPolicy(anchorToEnclosure: true)
+        
+        XCTAssertEqual(span(text, policy.computeRegion(text: text, caret: text.count)), text)
+    }
+    
+    func testComputeRegion_anchorToEnclosure_openStraightQuote_coversQuotedText() {
+        let text = "She said \"teh cat"
+        let policy = ActiveRegionPolicy(anchorToEnclosure: true)
+        
+        XCTAssertEqual(span(text, policy.computeRegion(text: text, caret: text.count)), "teh cat")
+    }
+    
+    func testComputeRegion_anchorDisabled_ignoresParenthetical() {
+        let text = "I said (like this"
+        
+        XCTAssertEqual(span(text, ActiveRegionPolicy.default.computeRegion(text: text, caret: text.count)), text)
+    }
+    
+    func testExplainRegion_windowed_matchesUnwindowedComputation() {
+        let sentences = [
+            "Alpha beta gamma. ", "Teh quick brown fox jumps! ", "Is it over? ",
+            "lorem ipsum dolor sit amet ", "a\n", "New line here\n\n", "(open paren ",
+            String(repeating: "x", count: 120) + " ", "word, word; word - word. ",
+        ]
+        var texts: [String] = []
+        for seed in 0..<24 {
+            var text = ""
+            var state = seed
+            while text.count < 1_500 {
+                state = (state * 1_103 + 12_345) % 9_973
+                text += sentences[state % sentences.count]
+            }
+            texts.append(text)
+        }
+        // Word-less buffers only have the paragraph and length cap to stop at
+        texts.append(String(repeating: "-", count: 2_000))
+        texts.append(String(repeating: "- ", count: 400) + "\n\n" + String(repeating: "?! ", count: 300))
+        let policies = [
+            ActiveRegionPolicy.default,
+            ActiveRegionPolicy(targetWords: 8, maxCharacters: 40),
+            ActiveRegionPolicy(targetWords: 50, maxCharacters: 200, paragraphBoundary: .newline),
+            ActiveRegionPolicy(paragraphBoundary: .none, wordSeparators: [",", ";"]),
+            ActiveRegionPolicy(scope: .lastWord),
+            ActiveRegionPolicy(maxCharacters: 100, anchorToEnclosure: true),
+        ]
+        
+        for text in texts {
+            for policy in policies {
+                for caret in stride(from: 0, through: text.count, by: 37) {
+                    let windowed = policy.explainRegion(text: text, caret: caret, windowed: true)
+                    let unwindowed = policy.explainRegion(text: text, caret: caret, windowed: false)
+                    XCTAssertEqual(windowed.region, unwindowed.region, "caret \(caret) in \(text.debugDescription)")
+                    XCTAssertEqual(windowed.startReason, unwindowed.startReason, "caret \(caret)")
+                }
+            }
+        }
+    }
+    
+    func testComputeRegion_longBufferFewWords_capsAtMaxCharacters() {
+        let text = String(repeating: "a", count: 2_000) + " " + String(repeating: "b", count: 600)
+        
+        let explanation = ActiveRegionPolicy.default.explainRegion(text: text, caret: text.count)
+        
+        XCTAssertEqual(explanation.region.length, 500)
+        XCTAssertEqual(explanation.startReason, .maxLength)
+    }
+    
+    func testComputeRegion_wordlessBuffer_capsAtMaxCharacters() {
+        let text = String(repeating: "-", count: 2_000)
+        
+        let explanation = ActiveRegionPolicy.default.explainRegion(text: text, caret: text.count)
+        
+        XCTAssertEqual(explanation.region, TextRegion(start: 1_500, end: 2_000))
+        XCTAssertEqual(explanation.startReason, .maxLength)
+    }
+    
+    func testExplainRegion_longSingleLineBuffer_matchesUnwindowedComputation() {
+        let text = String(repeating: "word ", count: 2_000)
+        let policy = ActiveRegionPolicy.default
+        
+        let windowed = policy.explainRegion(text: text, caret: text.count)
+        let unwindowed = policy.explainRegion(text: text, caret: text.count, windowed: false)
+        
+        XCTAssertEqual(windowed, unwindowed)
+    }
+    
+    func testPerformance_explainRegion_megabyteSingleLineBuffer() {
+        let text = String(repeating: "word ", count: 200_000)
+        let policy = ActiveRegionPolicy.default
+        
+        measure {
+            _ = policy.explainRegion(text: text, caret: text.count)
+        }
+    }
+}
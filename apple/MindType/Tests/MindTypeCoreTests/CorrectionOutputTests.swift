@@ -0,0 +1,88 @@
+/*╔══════════════════════════════════════════════════════════════╗
+  ║  ░  C O R R E C T I O N   O U T P U T   T E S T S  ░░░░░░░  ║
+  ╚══════════════════════════════════════════════════════════════╝
+*/
+
+import XCTest
+@testable import MindTypeCore
+
+final class CorrectionOutputTests: XCTestCase {
+    
+    private let text = "teh cat adn dog"
+    private let diffs = [
+        CorrectionDiff(start: 8, end: 11, text: "and", stage: .noise),
+        CorrectionDiff(start: 0, end: 3, text: "the", stage: .noise)
+    ]
+    
+    func testMakeDelta_twoCorrections_producesOrderedOperations() {
+        XCTAssertEqual(makeDelta(from: diffs), [
+            .delete(3), .insert("the"),
+            .retain(5),
+            .delete(3), .insert("and")
+        ])
+    }
+    
+    func testApplyDelta_matchesApplyingSpans() throws {
+        let delta = try XCTUnwrap(makeDelta(from: diffs))
+        
+        XCTAssertEqual(applyDelta(delta, to: text), "the cat and dog")
+        XCTAssertEqual(applyDelta(delta, to: text), applyDiffs(text: text, diffs: diffs, caret: text.count)?.text)
+    }
+    
+    func testMakeDelta_overlappingDiffs_returnsNil() {
+        let overlapping = [
+            CorrectionDiff(start: 0, end: 5, text: "a", stage: .noise),
+            CorrectionDiff(start: 3, end: 7, text: "b", stage: .noise)
+        ]
+        XCTAssertNil(makeDelta(from: overlapping))
+    }
+    
+    func testMakeDelta_invertedDiff_returnsNil() {
+        let inverted = [
+            CorrectionDiff(start: 5, end: 2, text: "a", stage: .noise),
+            CorrectionDiff(start: 6, end: 8, text: "b", stage: .noise)
+        ]
+        XCTAssertNil(makeDelta(from: inverted))
+    }
+    
+    func testMakeDelta_diffPastTextEnd_returnsNil() {
+        XCTAssertNil(makeDelta(from: diffs, textLength: 10))
+        XCTAssertNotNil(makeDelta(from: diffs, textLength: text.count))
+    }
+    
+    func testAnnotatedText_wrapsEachCorrectedSpan() throws {
+        let annotated = try XCTUnwrap(annotatedText(text, diffs: diffs))
+        
+        XCTAssertEqual(
+            annotated,
+            "<correction original=\"teh\" category=\"spelling\">the</correction> cat "
+                + "<correction original=\"adn\" category=\"spelling\">and</correction> dog"
+        )
+    }
+    
+    func testStripAnnotations_yieldsPlainCorrectedText() throws {
+        let source = "a < b & teh \"end\""
+        let fix = [CorrectionDiff(start: 8, end: 11, text: "the", stage: .noise)]
+        let annotated = try XCTUnwrap(annotatedText(source, diffs: fix))
+        
+        XCTAssertEqual(stripAnnotations(annotated), applyDiffs(text: source, diffs: fix, caret: source.count)?.text)
+    }
+    
+    func testTrackChangesText_markdown_showsOriginalThenReplacement() {
+        XCTAssertEqual(trackChangesText(text, diffs: diffs), "~~teh~~the cat ~~adn~~and dog")
+    }
+    
+    func testTrackChangesText_criticMarkup_balancesMarkers() throws {
+        let marked = try XCTUnwrap(trackChangesText(text, diffs: diffs, syntax: .criticMarkup))
+        
+        XCTAssertEqual(marked, "{--teh--}{++the++} cat {--adn--}{++and++} dog")
+        XCTAssertEqual(marked.components(separatedBy: "{--").count, marked.components(separatedBy: "--}").count)
+        XCTAssertEqual(marked.components(separatedBy: "{++").count, marked.components(separatedBy: "++}").count)
+    }
+    
+    func testTrackChangesText_pureInsertion_marksOnlyInsertion() {
+        let insertion = [CorrectionDiff(start: 3, end: 3, text: ",", stage: .context)]
+        
+        XCTAssertEqual(trackChangesText("yes sir", diffs: insertion, syntax: .criticMarkup), "yes{++,++} sir")
+    }
+}
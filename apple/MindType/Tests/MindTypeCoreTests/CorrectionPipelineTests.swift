@@ -0,0 +1,675 @@
+/*╔══════════════════════════════════════════════════════════════╗
+  ║  ░  C O R R E C T I O N   P I P E L I N E   T E S T S  ░░░  ║
+  ╚══════════════════════════════════════════════════════════════╝
+*/
+
+import XCTest
+@testable import MindTypeCore
+
+final class CorrectionPipelineTests: XCTestCase {
+    
+    private let paragraphText = "Intro.\n\nI saw teh cat"
+    
+    private func makePipeline(
+        _ replacements: [(String, String)] = [("teh", "the")],
+        config: PipelineConfiguration = .default
+    ) -> CorrectionPipeline {
+        CorrectionPipeline(lmAdapter: StubLMAdapter(replacements), config: config)
+    }
+    
+    func testRunCorrectionWave_documentOffsetBase_reportsAbsoluteOffsets() async throws {
+        let result = try await makePipeline().runCorrectionWave(text: paragraphText, caret: paragraphText.count)
+        
+        XCTAssertEqual(result.offsetBase, .document)
+        XCTAssertEqual(result.activeRegion, TextRegion(start: 8, end: 21))
+        XCTAssertEqual(result.diffs.first?.start, 8)
+        XCTAssertEqual(result.applied(to: paragraphText), "Intro.\n\nI saw the cat")
+    }
+    
+    func testRunCorrectionWave_regionOffsetBase_reportsRelativeOffsets() async throws {
+        let config = PipelineConfiguration(offsetBase: .region)
+        let result = try await makePipeline(config: config).runCorrectionWave(text: paragraphText, caret: paragraphText.count)
+        
+        XCTAssertEqual(result.offsetBase, .region)
+        XCTAssertEqual(result.activeRegion, TextRegion(start: 0, end: 13))
+        XCTAssertEqual(result.diffs.first?.start, 0)
+        XCTAssertEqual(result.applied(to: "I saw teh cat"), "I saw the cat")
+    }
+    
+    func testRebased_roundTrip_restoresDocumentOffsets() async throws {
+        let result = try await makePipeline().runCorrectionWave(text: paragraphText, caret: paragraphText.count)
+        
+        let roundTrip = result.rebased(to: .region).rebased(to: .document)
+        
+        XCTAssertEqual(roundTrip.diffs, result.diffs)
+        XCTAssertEqual(roundTrip.activeRegion, result.activeRegion)
+    }
+    
+    func testRunCorrectionWave_regionOffsetBase_emptyRegionIsRegionRelative() async throws {
+        let config = PipelineConfiguration(regionScope: .lastWord, offsetBase: .region)
+        let result = try await makePipeline(config: config).runCorrectionWave(text: "I saw te", caret: 8)
+        
+        XCTAssertEqual(result.offsetBase, .region)
+        XCTAssertEqual(result.activeRegion, TextRegion(start: 0, end: 0))
+        XCTAssertEqual(result.regionOrigin, 8)
+    }
+    
+    func testRunCorrectionWave_failingStage_isSkippedAndRecorded() async throws {
+        let adapter = StubLMAdapter([("teh", "the")], failWhenPromptContains: "Improve grammar")
+        let pipeline = CorrectionPipeline(lmAdapter: adapter)
+        
+        let result = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13)
+        
+        XCTAssertEqual(result.correctedText, "I saw the cat")
+        XCTAssertEqual(result.stagesApplied, [.noise])
+        XCTAssertEqual(result.stageErrors.map(\.stage), [.context])
+    }
+    
+    func testRunCorrectionWave_everyStageFails_throws() async {
+        let adapter = StubLMAdapter(failWhenPromptContains: "<|im_start|>")
+        let pipeline = CorrectionPipeline(lmAdapter: adapter)
+        
+        do {
+            _ = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13)
+            XCTFail("Expected the wave to throw when no stage succeeds")
+        } catch {
+            XCTAssertTrue(error is MindTypeError)
+        }
+    }
+    
+    func testRunCorrectionWave_stageFailedOnce_retriesOnIdenticalCall() async throws {
+        let pipeline = CorrectionPipeline(lmAdapter: StubLMAdapter([("teh", "the")], failingFirst: 1))
+        
+        let degraded = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13)
+        let retried = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13)
+        
+        XCTAssertEqual(degraded.stageErrors.map(\.stage), [.noise])
+        XCTAssertFalse(retried.cached)
+        XCTAssertTrue(retried.stageErrors.isEmpty)
+        XCTAssertEqual(retried.correctedText, "I saw the cat")
+    }
+    
+    func testRunCorrectionWave_stageFailedOnce_isNotRateLimited() async throws {
+        let pipeline = CorrectionPipeline(
+            lmAdapter: StubLMAdapter([("teh", "the")], failingFirst: 1),
+            config: PipelineConfiguration(minProcessIntervalMs: 60_000)
+        )
+        
+        _ = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13)
+        let retried = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13)
+        
+        XCTAssertFalse(retried.rateLimited)
+        XCTAssertEqual(retried.correctedText, "I saw the cat")
+    }
+    
+    func testHistory_twoWaves_returnsNewestFirst() async throws {
+        let pipeline = makePipeline([("teh", "the"), ("adn", "and")], config: PipelineConfiguration(historyLimit: 5))
+        
+        _ = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13)
+        _ = try await pipeline.runCorrectionWave(text: "this adn that", caret: 13)
+        
+        let history = await pipeline.history(max: 2)
+        XCTAssertEqual(history.map(\.originalText), ["this adn that", "I saw teh cat"])
+        XCTAssertEqual(history.first?.result.correctedText, "this and that")
+        
+        await pipeline.clearHistory()
+        let cleared = await pipeline.history()
+        XCTAssertTrue(cleared.isEmpty)
+    }
+    
+    func testHistory_negativeMax_returnsEmpty() async throws {
+        let pipeline = makePipeline(config: PipelineConfiguration(historyLimit: 5))
+        
+        _ = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13)
+        
+        let history = await pipeline.history(max: -1)
+        XCTAssertTrue(history.isEmpty)
+    }
+    
+    func testHistory_disabledByDefault() async throws {
+        let pipeline = makePipeline()
+        
+        _ = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13)
+        
+        let history = await pipeline.history()
+        XCTAssertTrue(history.isEmpty)
+    }
+    
+    func testRunCorrectionWave_identicalInputs_returnsCachedResult() async throws {
+        let adapter = StubLMAdapter([("teh", "the")])
+        let pipeline = CorrectionPipeline(lmAdapter: adapter)
+        
+        let first = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13)
+        let callsAfterFirst = await adapter.generateCount
+        let second = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13)
+        let callsAfterSecond = await adapter.generateCount
+        
+        XCTAssertFalse(first.cached)
+        XCTAssertTrue(second.cached)
+        XCTAssertEqual(second.diffs, first.diffs)
+        XCTAssertEqual(callsAfterSecond, callsAfterFirst)
+    }
+    
+    func testRunCorrectionWave_editOutsideRegion_isNotCached() async throws {
+        let pipeline = makePipeline(config: PipelineConfiguration(activeRegionWords: 5))
+        let before = "Xne two. I saw teh cat"
+        let after = "One two. I saw teh cat"
+        
+        _ = try await pipeline.runCorrectionWave(text: before, caret: before.count)
+        let result = try await pipeline.runCorrectionWave(text: after, caret: after.count)
+        
+        XCTAssertFalse(result.cached)
+        XCTAssertEqual(result.correctedText, "One two. I saw the cat")
+    }
+    
+    func testRunCorrectionWave_toneChange_invalidatesCache() async throws {
+        let pipeline = makePipeline()
+        
+        _ = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13)
+        let retoned = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13, toneTarget: .casual)
+        
+        XCTAssertFalse(retoned.cached)
+    }
+    
+    func testConfidenceHistogram_knownConfidences_fillsExpectedBuckets() {
+        let histogram = confidenceHistogram([0.05, 0.15, 0.55, 0.9, 0.95, 1.0])
+        
+        XCTAssertEqual(histogram, [1, 1, 0, 0, 0, 1, 0, 0, 0, 3])
+    }
+    
+    func testRunCorrectionWave_histogramEnabled_countsCandidates() async throws {
+        let config = PipelineConfiguration(includeConfidenceHistogram: true)
+        let result = try await makePipeline(config: config).runCorrectionWave(text: "I saw teh cat", caret: 13)
+        
+        XCTAssertEqual(result.confidenceHistogram?.count, 10)
+        XCTAssertEqual(result.confidenceHistogram?.reduce(0, +), 1)
+        XCTAssertEqual(result.confidenceHistogram?[9], 1)
+    }
+    
+    func testRunCorrectionWave_histogramDisabled_omitsHistogram() async throws {
+        let result = try await makePipeline().runCorrectionWave(text: "I saw teh cat", caret: 13)
+        
+        XCTAssertNil(result.confidenceHistogram)
+    }
+    
+    func testRunCorrectionWave_separateFields_keepIndependentCaches() async throws {
+        let pipeline = makePipeline()
+        
+        _ = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13, fieldId: "subject")
+        let otherField = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13, fieldId: "body")
+        let sameField = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13, fieldId: "subject")
+        
+        XCTAssertFalse(otherField.cached)
+        XCTAssertTrue(sameField.cached)
+    }
+    
+    func testRunCorrectionWave_beyondFieldCapacity_evictsOldestField() async throws {
+        let pipeline = makePipeline(config: PipelineConfiguration(fieldStateCapacity: 2))
+        
+        _ = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13, fieldId: "a")
+        _ = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13, fieldId: "b")
+        _ = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13, fieldId: "c")
+        let evicted = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13, fieldId: "a")
+        let retained = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13, fieldId: "c")
+        
+        XCTAssertFalse(evicted.cached)
+        XCTAssertTrue(retained.cached)
+    }
+    
+    func testRunCorrectionWave_withinMinInterval_isRateLimited() async throws {
+        let adapter = StubLMAdapter([("teh", "the")])
+        let config = PipelineConfiguration(minProcessIntervalMs: 60_000)
+        let pipeline = CorrectionPipeline(lmAdapter: adapter, config: config)
+        
+        let first = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13)
+        let callsAfterFirst = await adapter.generateCount
+        let second = try await pipeline.runCorrectionWave(text: "I saw teh cat and", caret: 17)
+        let callsAfterSecond = await adapter.generateCount
+        
+        XCTAssertFalse(first.rateLimited)
+        XCTAssertTrue(second.rateLimited)
+        XCTAssertEqual(second.diffs, first.diffs)
+        XCTAssertEqual(second.correctedText, "I saw the cat and")
+        XCTAssertEqual(callsAfterSecond, callsAfterFirst)
+    }
+    
+    func testRunCorrectionWave_rateLimitedAfterEditInsideFix_dropsStaleDiffs() async throws {
+        let pipeline = makePipeline(config: PipelineConfiguration(minProcessIntervalMs: 60_000))
+        
+        _ = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13)
+        let second = try await pipeline.runCorrectionWave(text: "I saw ten cat", caret: 13)
+        
+        XCTAssertTrue(second.rateLimited)
+        XCTAssertTrue(second.diffs.isEmpty)
+        XCTAssertNil(second.correctedText)
+    }
+    
+    func testRunCorrectionWave_rateLimitOff_recomputesEveryCall() async throws {
+        let pipeline = makePipeline()
+        
+        _ = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13)
+        let second = try await pipeline.runCorrectionWave(text: "I saw teh cat and", caret: 17)
+        
+        XCTAssertFalse(second.rateLimited)
+        XCTAssertFalse(second.cached)
+    }
+    
+    func testRunCorrectionWave_caretBoundaryDisallowed_suppressesCorrectionEndingAtCaret() async throws {
+        let config = PipelineConfiguration(allowCaretBoundaryCorrections: false)
+        let result = try await makePipeline([("cta", "cat")], config: config)
+            .runCorrectionWave(text: "I saw the cta", caret: 13)
+        
+        XCTAssertTrue(result.diffs.isEmpty)
+        XCTAssertNil(result.correctedText)
+    }
+    
+    func testRunCorrectionWave_caretBoundaryDisallowed_keepsTrailingWordsFixBeforeCaret() async throws {
+        let config = PipelineConfiguration(allowCaretBoundaryCorrections: false)
+        let result = try await makePipeline(config: config).runCorrectionWave(text: "I saw teh cat", caret: 13)
+        let diff = try XCTUnwrap(result.diffs.first)
+        
+        XCTAssertLessThan(diff.end, 13)
+        XCTAssertEqual(diff, CorrectionDiff(start: 7, end: 9, text: "he", stage: .noise, confidence: 0.9))
+        XCTAssertEqual(result.correctedText, "I saw the cat")
+    }
+    
+    func testRunCorrectionWave_caretBoundaryAllowed_keepsCorrectionEndingAtCaret() async throws {
+        let result = try await makePipeline().runCorrectionWave(text: "I saw teh cat", caret: 13)
+        
+        XCTAssertEqual(result.diffs.first?.end, 13)
+    }
+    
+    func testRunCorrectionWave_caretBoundaryDisallowed_keepsCorrectionBeforeCaret() async throws {
+        let config = PipelineConfiguration(regionScope: .lastWord, allowCaretBoundaryCorrections: false)
+        let result = try await makePipeline(config: config).runCorrectionWave(text: "I saw teh ", caret: 10)
+        
+        XCTAssertEqual(result.diffs, [CorrectionDiff(start: 7, end: 9, text: "he", stage: .noise, confidence: 0.9)])
+        XCTAssertEqual(result.correctedText, "I saw the ")
+    }
+    
+    func testRunCorrectionWave_editableRange_dropsCorrectionsOutsideIt() async throws {
+        let text = "teh form: teh cat"
+        let result = try await makePipeline().runCorrectionWave(
+            text: text,
+            caret: text.count,
+            editableRange: TextRegion(start: 10, end: 17)
+        )
+        
+        XCTAssertEqual(result.activeRegion, TextRegion(start: 10, end: 17))
+        XCTAssertEqual(result.correctedText, "teh form: the cat")
+        XCTAssertTrue(result.diffs.allSatisfy { $0.start >= 10 && $0.end <= 17 })
+    }
+    
+    func testRunCorrectionWave_editableRangeBeforeRegion_clampsBand() async throws {
+        let text = "teh form: cat"
+        let result = try await makePipeline().runCorrectionWave(
+            text: text,
+            caret: text.count,
+            editableRange: TextRegion(start: 0, end: 8)
+        )
+        
+        XCTAssertEqual(result.activeRegion, TextRegion(start: 0, end: 8))
+        XCTAssertEqual(result.correctedText, "the form: cat")
+    }
+    
+    func testRunCorrectionWave_reprocessingCorrectedText_isNoOp() async throws {
+        let corpus = ["I saw teh cat", "this adn that", "teh dog adn teh cat", "nothing to fix here"]
+        let replacements = [("teh", "the"), ("adn", "and")]
+        
+        for text in corpus {
+            let pipeline = makePipeline(replacements)
+            let first = try await pipeline.runCorrectionWave(text: text, caret: text.count)
+            let corrected = first.correctedText ?? text
+            
+            let second = try await pipeline.runCorrectionWave(text: corrected, caret: corrected.count)
+            
+            XCTAssertTrue(second.diffs.isEmpty, "Re-correcting \"\(corrected)\" should reach a fixpoint")
+            XCTAssertNil(second.correctedText)
+        }
+    }
+    
+    func testRunCorrectionWave_churnLimitZero_neverUndoesPreviousCorrection() async throws {
+        let pipeline = CorrectionPipeline(
+            lmAdapter: CaseTogglingLMAdapter(),
+            config: PipelineConfiguration(churnSuppressionLimit: 0)
+        )
+        let text = "I saw teh cat"
+        
+        let first = try await pipeline.runCorrectionWave(text: text, caret: text.count)
+        let corrected = try XCTUnwrap(first.correctedText)
+        let second = try await pipeline.runCorrectionWave(text: corrected, caret: corrected.count)
+        
+        XCTAssertEqual(corrected, "i SAW TEH CAT")
+        XCTAssertTrue(second.diffs.isEmpty, "Re-correcting \"\(corrected)\" should not undo the first wave")
+        XCTAssertNil(second.correctedText)
+    }
+    
+    func testRunCorrectionWave_unrelatedFixWithMirroredCharacters_isStillEmitted() async throws {
+        let cases = [
+            ("I recieve", "I receive your beleif", "I receive your belief"),
+            ("I want a apple", "I want an apple to runn fast", "I want an apple to run fast"),
+        ]
+        let pipeline = makePipeline(
+            [("recieve", "receive"), ("beleif", "belief"), ("a apple", "an apple"), ("runn", "run")],
+            config: PipelineConfiguration(churnSuppressionLimit: 0)
+        )
+        
+        for (first, second, expected) in cases {
+            let earlier = try await pipeline.runCorrectionWave(text: first, caret: first.count)
+            let later = try await pipeline.runCorrectionWave(text: second, caret: second.count)
+            
+            XCTAssertFalse(earlier.diffs.isEmpty, first)
+            XCTAssertEqual(later.correctedText, expected)
+        }
+    }
+    
+    func testRunCorrectionWave_afterInvalidation_forgetsPreviousCorrection() async throws {
+        let pipeline = CorrectionPipeline(
+            lmAdapter: CaseTogglingLMAdapter(),
+            config: PipelineConfiguration(churnSuppressionLimit: 0)
+        )
+        
+        let first = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13)
+        let corrected = try XCTUnwrap(first.correctedText)
+        await pipeline.invalidateCache()
+        let second = try await pipeline.runCorrectionWave(text: corrected, caret: corrected.count)
+        
+        XCTAssertEqual(second.correctedText, "I saw teh cat")
+    }
+    
+    /// Feed each wave's corrected text back in, as a host applying every correction would
+    private func reprocess(_ text: String, waves: Int, with pipeline: CorrectionPipeline) async throws -> [String] {
+        var texts = [text]
+        for _ in 0..<waves {
+            let current = texts[texts.count - 1]
+            let result = try await pipeline.runCorrectionWave(text: current, caret: current.count)
+            texts.append(result.correctedText ?? current)
+        }
+        return texts
+    }
+    
+    func testChurn_oscillatingModel_countsFlips() async throws {
+        let pipeline = CorrectionPipeline(lmAdapter: CaseTogglingLMAdapter())
+        
+        let texts = try await reprocess("I saw teh cat", waves: 4, with: pipeline)
+        let churn = await pipeline.churn()
+        
+        XCTAssertEqual(texts, ["I saw teh cat", "i SAW TEH CAT", "I saw teh cat", "i SAW TEH CAT", "I saw teh cat"])
+        XCTAssertEqual(churn, [SpanChurn(region: TextRegion(start: 0, end: 13), flips: 3)])
+    }
+    
+    func testChurn_suppressionLimit_stopsFlippingAfterLimit() async throws {
+        let pipeline = CorrectionPipeline(
+            lmAdapter: CaseTogglingLMAdapter(),
+            config: PipelineConfiguration(churnSuppressionLimit: 2)
+        )
+        
+        let texts = try await reprocess("I saw teh cat", waves: 6, with: pipeline)
+        let churn = await pipeline.churn()
+        
+        XCTAssertEqual(Array(texts.suffix(4)), Array(repeating: "i SAW TEH CAT", count: 4))
+        XCTAssertEqual(churn.first?.flips, 2)
+    }
+    
+    func testChurn_stableCorrections_reportNoChurn() async throws {
+        let pipeline = makePipeline()
+        
+        _ = try await reprocess("I saw teh cat", waves: 3, with: pipeline)
+        let churn = await pipeline.churn()
+        
+        XCTAssertTrue(churn.isEmpty)
+    }
+    
+    func testRunCorrectionWave_typoOnlyWave_isSafeToAutoApply() async throws {
+        let result = try await makePipeline().runCorrectionWave(text: "I saw teh cat", caret: 13)
+        
+        XCTAssertEqual(result.diffs.first?.safeToAutoApply, true)
+    }
+    
+    func testRunCorrectionWave_strictPolicy_marksWaveAsSuggestion() async throws {
+        let config = PipelineConfiguration(autoApplyPolicy: AutoApplyPolicy(minimumConfidence: 0.95))
+        let result = try await makePipeline(config: config).runCorrectionWave(text: "I saw teh cat", caret: 13)
+        
+        XCTAssertEqual(result.diffs.first?.safeToAutoApply, false)
+    }
+    
+    func testLastResult_afterWave_matchesReturnedResult() async throws {
+        let pipeline = makePipeline()
+        
+        let result = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13, fieldId: "body")
+        let last = await pipeline.lastResult(fieldId: "body")
+        
+        XCTAssertEqual(last?.diffs, result.diffs)
+        XCTAssertEqual(last?.correctedText, "I saw the cat")
+    }
+    
+    func testLastResult_afterEmptyRegionWave_reportsNoDiffs() async throws {
+        let pipeline = makePipeline(config: PipelineConfiguration(regionScope: .lastWord))
+        
+        _ = try await pipeline.runCorrectionWave(text: "I saw teh ", caret: 10, fieldId: "body")
+        let empty = try await pipeline.runCorrectionWave(text: "I saw teh ca", caret: 12, fieldId: "body")
+        let last = await pipeline.lastResult(fieldId: "body")
+        
+        XCTAssertTrue(empty.diffs.isEmpty)
+        XCTAssertEqual(last?.diffs, [])
+        XCTAssertNil(last?.correctedText)
+    }
+    
+    func testLastResult_unprocessedField_isNil() async {
+        let last = await makePipeline().lastResult(fieldId: "never-seen")
+        
+        XCTAssertNil(last)
+    }
+    
+    func testWouldAutoApply_matchesWaveFlag() async throws {
+        let pipeline = makePipeline(config: PipelineConfiguration(autoApplyPolicy: AutoApplyPolicy(minimumConfidence: 0.95)))
+        
+        let result = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13)
+        let diff = try XCTUnwrap(result.diffs.first)
+        
+        XCTAssertEqual(
+            pipeline.wouldAutoApply(stage: diff.stage, category: diff.category, confidence: diff.confidence),
+            diff.safeToAutoApply
+        )
+        XCTAssertFalse(pipeline.wouldAutoApply(stage: .tone, confidence: 1.0))
+    }
+    
+    func testRunCorrectionWave_includeCandidates_coversAcceptedStages() async throws {
+        let config = PipelineConfiguration(includeCandidates: true)
+        let result = try await makePipeline(config: config).runCorrectionWave(text: "I saw teh cat", caret: 13)
+        let candidates = try XCTUnwrap(result.candidates)
+        
+        XCTAssertTrue(Set(result.stagesApplied).isSubset(of: candidates.map(\.stage)))
+    }
+    
+    func testRunCorrectionWave_includeCandidates_keepsSuppressedCorrection() async throws {
+        let config = PipelineConfiguration(includeCandidates: true, allowCaretBoundaryCorrections: false)
+        let result = try await makePipeline([("cta", "cat")], config: config)
+            .runCorrectionWave(text: "I saw the cta", caret: 13)
+        
+        XCTAssertTrue(result.diffs.isEmpty)
+        XCTAssertEqual(result.candidates?.count, 1)
+    }
+    
+    func testSetConfidenceScorer_noiseOverride_setsCandidateConfidence() async throws {
+        let pipeline = makePipeline(config: PipelineConfiguration(includeCandidates: true))
+        await pipeline.setConfidenceScorer({ _, _ in 0.95 }, for: .noise)
+        
+        let result = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13)
+        
+        XCTAssertEqual(result.diffs.first?.confidence, 0.95)
+        XCTAssertEqual(result.candidates?.first?.confidence, 0.95)
+    }
+    
+    func testSetConfidenceScorer_removedOverride_restoresBuiltInConfidence() async throws {
+        let pipeline = makePipeline()
+        await pipeline.setConfidenceScorer({ _, _ in 0.95 }, for: .noise)
+        await pipeline.setConfidenceScorer(nil, for: .noise)
+        
+        let result = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13)
+        
+        XCTAssertEqual(result.diffs.first?.confidence, 0.9)
+    }
+    
+    func testSetConfidenceScorer_lowScore_dropsCorrectionBelowThreshold() async throws {
+        let pipeline = makePipeline()
+        await pipeline.setConfidenceScorer({ _, _ in 0.1 }, for: .noise)
+        
+        let result = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13)
+        
+        XCTAssertTrue(result.diffs.isEmpty)
+    }
+    
+    func testSetConfidenceScorer_nonFiniteScore_dropsCorrection() async throws {
+        for score in [Double.nan, .infinity] {
+            let pipeline = makePipeline()
+            await pipeline.setConfidenceScorer({ _, _ in score }, for: .noise)
+            
+            let result = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13)
+            
+            XCTAssertTrue(result.diffs.isEmpty, "score \(score)")
+        }
+    }
+    
+    func testSetAuditHandler_computedWave_reportsEachCorrection() async throws {
+        let pipeline = makePipeline()
+        let audited = AuditRecorder()
+        await pipeline.setAuditHandler { audited.record($0) }
+        
+        let result = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13)
+        _ = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13)
+        
+        XCTAssertEqual(audited.diffs, result.diffs)
+    }
+    
+    func testSetAuditHandler_cleared_stopsReporting() async throws {
+        let pipeline = makePipeline()
+        let audited = AuditRecorder()
+        await pipeline.setAuditHandler { audited.record($0) }
+        await pipeline.setAuditHandler(nil)
+        
+        _ = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13)
+        
+        XCTAssertTrue(audited.diffs.isEmpty)
+    }
+    
+    func testInvalidateCache_warmField_recomputesNextWave() async throws {
+        let pipeline = makePipeline()
+        
+        _ = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13, fieldId: "body")
+        await pipeline.invalidateCache(fieldId: "body")
+        let result = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13, fieldId: "body")
+        
+        XCTAssertFalse(result.cached)
+    }
+    
+    func testInvalidateCache_keepsLastResultAndOtherFields() async throws {
+        let pipeline = makePipeline()
+        
+        _ = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13, fieldId: "subject")
+        _ = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13, fieldId: "body")
+        await pipeline.invalidateCache(fieldId: "body")
+        let lastBody = await pipeline.lastResult(fieldId: "body")
+        let subject = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13, fieldId: "subject")
+        
+        XCTAssertNotNil(lastBody)
+        XCTAssertTrue(subject.cached)
+    }
+    
+    func testInvalidateAllCaches_recomputesEveryField() async throws {
+        let pipeline = makePipeline()
+        
+        _ = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13, fieldId: "subject")
+        _ = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13, fieldId: "body")
+        await pipeline.invalidateAllCaches()
+        let subject = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13, fieldId: "subject")
+        let body = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13, fieldId: "body")
+        
+        XCTAssertFalse(subject.cached)
+        XCTAssertFalse(body.cached)
+    }
+    
+    func testLatencyStats_countsComputedWavesOnly() async throws {
+        let pipeline = makePipeline()
+        let before = await pipeline.latencyStats()
+        
+        _ = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13)
+        _ = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13)
+        let after = await pipeline.latencyStats()
+        
+        XCTAssertNil(before)
+        XCTAssertEqual(after?.count, 1)
+    }
+    
+    func testSetConfidenceThreshold_aboveCandidateConfidence_dropsCorrection() async throws {
+        let pipeline = makePipeline()
+        
+        _ = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13)
+        await pipeline.setConfidenceThreshold(0.95)
+        let result = try await pipeline.runCorrectionWave(text: "I saw teh cat", caret: 13)
+        
+        XCTAssertTrue(result.diffs.isEmpty)
+        XCTAssertFalse(result.cached)
+    }
+    
+    func testSetConfidenceThreshold_outOfRange_isClamped() async {
+        let pipeline = makePipeline()
+        let initial = await pipeline.confidenceThreshold
+        
+        let applied = await pipeline.setConfidenceThreshold(5.0)
+        let clamped = await pipeline.confidenceThreshold
+        
+        XCTAssertEqual(initial, PipelineConfiguration.default.confidenceThreshold)
+        XCTAssertEqual(applied, 1.0)
+        XCTAssertEqual(clamped, 1.0)
+    }
+    
+    func testRunCorrectionWave_baseTextDiffersOutsideRegion_isNotStale() async throws {
+        let pipeline = makePipeline(config: PipelineConfiguration(activeRegionWords: 5))
+        let live = "One two. I saw teh cat"
+        
+        let result = try await pipeline.runCorrectionWave(text: live, caret: live.count, baseText: "Xne two. I saw teh cat")
+        
+        XCTAssertFalse(result.stale)
+    }
+    
+    func testRunCorrectionWave_baseTextDiffersInsideRegion_isStale() async throws {
+        let live = "I saw teh cat"
+        
+        let result = try await makePipeline().runCorrectionWave(text: live, caret: live.count, baseText: "I saw teh cot")
+        let unchecked = try await makePipeline().runCorrectionWave(text: live, caret: live.count)
+        
+        XCTAssertTrue(result.stale)
+        XCTAssertFalse(unchecked.stale)
+    }
+    
+    func testRunCorrectionWave_clampedConfiguration_reportsWarningsOnResult() async throws {
+        let pipeline = makePipeline(config: PipelineConfiguration(confidenceThreshold: 5.0, regionScope: .lastWord))
+        
+        let computed = try await pipeline.runCorrectionWave(text: "I saw teh ", caret: 10)
+        let emptyRegion = try await pipeline.runCorrectionWave(text: "I saw te", caret: 8)
+        
+        XCTAssertEqual(computed.warnings, PipelineConfiguration(confidenceThreshold: 5.0).warnings)
+        XCTAssertEqual(emptyRegion.warnings.count, 1)
+    }
+    
+    func testRunCorrectionWave_validConfiguration_hasNoWarnings() async throws {
+        let result = try await makePipeline().runCorrectionWave(text: "I saw teh cat", caret: 13)
+        
+        XCTAssertTrue(result.warnings.isEmpty)
+    }
+}
+
+/// Thread-safe sink for audit handler callbacks
+private final class AuditRecorder: @unchecked Sendable {
+    private let lock = NSLock()
+    private var recorded: [CorrectionDiff] = []
+    
+    var diffs: [CorrectionDiff] {
+        lock.withLock { recorded }
+    }
+    
+    func record(_ diff: CorrectionDiff) {
+        lock.withLock { recorded.append(diff) }
+    }
+}
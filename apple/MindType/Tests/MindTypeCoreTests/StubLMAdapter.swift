@@ -0,0 +1,72 @@
+/*╔══════════════════════════════════════════════════════════════╗
+  ║  ░  S T U B   L M   A D A P T E R  ░░░░░░░░░░░░░░░░░░░░░░░  ║
+  ╚══════════════════════════════════════════════════════════════╝
+*/
+
+import Foundation
+@testable import MindTypeCore
+
+/// Deterministic adapter for pipeline tests: echoes the prompt snippet
+/// with fixed substring replacements applied
+actor StubLMAdapter: LMAdapter {
+    private let replacements: [(String, String)]
+    private let failureMarker: String?
+    private var remainingFailures: Int
+    private(set) var generateCount = 0
+    
+    /// - Parameters:
+    ///   - failWhenPromptContains: throw for any prompt containing this text
+    ///   - failingFirst: throw for this many calls before behaving normally
+    init(
+        _ replacements: [(String, String)] = [],
+        failWhenPromptContains failureMarker: String? = nil,
+        failingFirst remainingFailures: Int = 0
+    ) {
+        self.replacements = replacements
+        self.failureMarker = failureMarker
+        self.remainingFailures = remainingFailures
+    }
+    
+    var isReady: Bool { true }
+    var status: LMStatus { .ready }
+    
+    func initialize(config: LMConfiguration) async throws {}
+    
+    func generate(prompt: String, maxTokens: Int) async throws -> String {
+        generateCount += 1
+        if let failureMarker, prompt.contains(failureMarker) {
+            throw MindTypeError.generationFailed("stub failure")
+        }
+        if remainingFailures > 0 {
+            remainingFailures -= 1
+            throw MindTypeError.generationFailed("stub failure")
+        }
+        return replacements.reduce(Self.snippet(from: prompt)) { result, pair in
+            result.replacingOccurrences(of: pair.0, with: pair.1)
+        }
+    }
+    
+    /// The last user turn of a chat prompt built by `PromptBuilder`
+    static func snippet(from prompt: String) -> String {
+        let marker = "<|im_start|>user\n"
+        guard let userStart = prompt.range(of: marker, options: .backwards) else { return "" }
+        let rest = prompt[userStart.upperBound...]
+        let end = rest.range(of: "\n<|im_end|>")?.lowerBound ?? rest.endIndex
+        return String(rest[..<end])
+    }
+}
+
+/// Non-idempotent adapter: the noise stage swaps the case of every letter and other stages
+/// echo, so feeding a wave's output back in would undo it if the pipeline allowed
+actor CaseTogglingLMAdapter: LMAdapter {
+    var isReady: Bool { true }
+    var status: LMStatus { .ready }
+    
+    func initialize(config: LMConfiguration) async throws {}
+    
+    func generate(prompt: String, maxTokens: Int) async throws -> String {
+        let snippet = StubLMAdapter.snippet(from: prompt)
+        guard prompt.contains("garbled speed-typing") else { return snippet }
+        return String(snippet.map { $0.isUppercase ? Character($0.lowercased()) : Character($0.uppercased()) })
+    }
+}
@@ -0,0 +1,168 @@
+/*╔══════════════════════════════════════════════════════════════╗
+  ║  ░  C O R E   T Y P E S   T E S T S  ░░░░░░░░░░░░░░░░░░░░░  ║
+  ╚══════════════════════════════════════════════════════════════╝
+*/
+
+import XCTest
+@testable import MindTypeCore
+
+final class TypesTests: XCTestCase {
+    
+    func testCorrectionDiff_withoutCategory_usesStageDefault() {
+        XCTAssertEqual(CorrectionDiff(start: 0, end: 3, text: "the", stage: .noise).category, .spelling)
+        XCTAssertEqual(CorrectionDiff(start: 0, end: 3, text: "the", stage: .context).category, .grammar)
+        XCTAssertEqual(CorrectionDiff(start: 0, end: 3, text: "the", stage: .tone).category, .tone)
+    }
+    
+    func testCorrectionDiff_explicitCategory_isIndependentOfStage() {
+        let quotes = CorrectionDiff(start: 0, end: 1, text: "\u{201C}", stage: .noise, category: .typography)
+        let capital = CorrectionDiff(start: 0, end: 1, text: "I", stage: .noise, category: .capitalization)
+        
+        XCTAssertEqual(quotes.stage, .noise)
+        XCTAssertEqual(quotes.category, .typography)
+        XCTAssertEqual(capital.category, .capitalization)
+    }
+    
+    func testCorrectionDiff_category_roundTripsThroughJSON() throws {
+        let diff = CorrectionDiff(start: 2, end: 4, text: ", ", stage: .noise, category: .punctuation)
+        
+        let data = try JSONEncoder().encode(diff)
+        let decoded = try JSONDecoder().decode(CorrectionDiff.self, from: data)
+        
+        XCTAssertEqual(decoded, diff)
+        XCTAssertTrue(String(decoding: data, as: UTF8.self).contains("\"category\":\"punctuation\""))
+    }
+    
+    func testCorrectionDiff_jsonWithoutCategoryOrAutoApply_decodesWithDefaults() throws {
+        let legacy = #"{"start":6,"end":9,"text":"the","stage":"noise","confidence":0.9}"#
+        
+        let decoded = try JSONDecoder().decode(CorrectionDiff.self, from: Data(legacy.utf8))
+        
+        XCTAssertEqual(decoded, CorrectionDiff(start: 6, end: 9, text: "the", stage: .noise, confidence: 0.9))
+        XCTAssertEqual(decoded.category, CorrectionStage.noise.defaultCategory)
+        XCTAssertTrue(decoded.safeToAutoApply)
+    }
+    
+    func testTextRegionOffsets_mixedASCIIAndEmoji_agreeAcrossIndexSpaces() throws {
+        let text = "hi \u{1F44B}\u{1F3FD} there"
+        
+        let word = try XCTUnwrap(TextRegion(start: 5, end: 10).offsets(in: text))
+        XCTAssertEqual(word.characters, 5..<10)
+        XCTAssertEqual(word.utf8, 12..<17)
+        XCTAssertEqual(word.utf16, 8..<13)
+        
+        let emoji = try XCTUnwrap(TextRegion(start: 3, end: 4).offsets(in: text))
+        XCTAssertEqual(emoji.utf8, 3..<11)
+        XCTAssertEqual(emoji.utf16, 3..<7)
+        
+        let utf8Slice = Array(text.utf8)[word.utf8]
+        XCTAssertEqual(String(decoding: utf8Slice, as: UTF8.self), "there")
+        XCTAssertEqual((text as NSString).substring(with: NSRange(location: word.utf16.lowerBound, length: word.utf16.count)), "there")
+    }
+    
+    func testTextRegionOffsets_pastEnd_returnsNil() {
+        XCTAssertNil(TextRegion(start: 0, end: 10).offsets(in: "short"))
+    }
+    
+    func testPipelineConfigurationStages_toneDisabled_listsToneAsDisabled() {
+        let stages = PipelineConfiguration(toneTarget: .none).stages
+        
+        XCTAssertEqual(stages.map(\.stage), [.noise, .context, .tone])
+        XCTAssertEqual(stages.map(\.order), [0, 1, 2])
+        XCTAssertEqual(stages.map(\.enabled), [true, true, false])
+    }
+    
+    func testPipelineConfigurationStages_toneTargetSet_enablesTone() {
+        let stages = PipelineConfiguration(toneTarget: .professional).stages
+        
+        XCTAssertTrue(stages.allSatisfy(\.enabled))
+    }
+    
+    func testSafeToAutoApply_confidentSpacingFix_isSafe() {
+        let spacing = CorrectionDiff(start: 3, end: 5, text: " ", stage: .noise, confidence: 0.95, category: .punctuation)
+        
+        XCTAssertTrue(spacing.safeToAutoApply)
+    }
+    
+    func testSafeToAutoApply_grammarAndToneSuggestions_areNotSafe() {
+        let homophone = CorrectionDiff(start: 0, end: 4, text: "you're", stage: .context, confidence: 0.95)
+        let tone = CorrectionDiff(start: 0, end: 4, text: "Hello", stage: .tone, confidence: 1.0, category: .spelling)
+        let hesitant = CorrectionDiff(start: 0, end: 3, text: "the", stage: .noise, confidence: 0.6)
+        
+        XCTAssertFalse(homophone.safeToAutoApply)
+        XCTAssertFalse(tone.safeToAutoApply)
+        XCTAssertFalse(hesitant.safeToAutoApply)
+    }
+    
+    func testToneTargetNormalizing_caseAndWhitespaceVariants_mapToSameTone() {
+        let variants = ["Professional", "professional ", "PROFESSIONAL", "\tProfessional\n"]
+        
+        XCTAssertEqual(variants.map { ToneTarget(normalizing: $0) }, Array(repeating: .professional, count: variants.count))
+    }
+    
+    func testToneTargetNormalizing_unknownTone_isRejected() {
+        XCTAssertNil(ToneTarget(normalizing: "formal"))
+        XCTAssertNil(ToneTarget(normalizing: ""))
+    }
+    
+    func testPipelineConfiguration_outOfRangeThreshold_clampsAndWarns() {
+        let high = PipelineConfiguration(confidenceThreshold: 5.0)
+        let negative = PipelineConfiguration(confidenceThreshold: -1.0, temperature: 3.0)
+        
+        XCTAssertEqual(high.confidenceThreshold, 1.0)
+        XCTAssertEqual(high.warnings.count, 1)
+        XCTAssertTrue(high.warnings[0].contains("confidenceThreshold"))
+        XCTAssertEqual(negative.confidenceThreshold, 0.5)
+        XCTAssertEqual(negative.temperature, 1.0)
+        XCTAssertEqual(negative.warnings.count, 2)
+    }
+    
+    func testPipelineConfiguration_inRangeValues_haveNoWarnings() {
+        XCTAssertTrue(PipelineConfiguration.default.warnings.isEmpty)
+    }
+    
+    func testDirection_hebrewCorrection_isRightToLeft() {
+        let diff = CorrectionDiff(start: 0, end: 4, text: "שלום", stage: .noise)
+        
+        XCTAssertEqual(diff.direction, .rtl)
+    }
+    
+    func testDirection_latinCorrection_isLeftToRight() {
+        let diff = CorrectionDiff(start: 0, end: 3, text: "the", stage: .noise)
+        let punctuation = CorrectionDiff(start: 0, end: 1, text: ".", stage: .noise)
+        
+        XCTAssertEqual(diff.direction, .ltr)
+        XCTAssertEqual(punctuation.direction, .ltr)
+    }
+    
+    func testTextRegionCovering_scatteredSpans_spansFirstStartToLastEnd() {
+        let spans = [TextRegion(start: 12, end: 15), TextRegion(start: 2, end: 5), TextRegion(start: 30, end: 34)]
+        
+        XCTAssertEqual(TextRegion.covering(spans), TextRegion(start: 2, end: 34))
+    }
+    
+    func testTextRegionCovering_noSpans_isNil() {
+        XCTAssertNil(TextRegion.covering([]))
+    }
+    
+    func testLatencyStats_knownSamples_reportsNearestRankPercentiles() throws {
+        let stats = try XCTUnwrap(LatencyStats(samples: (1...100).reversed().map(Double.init)))
+        
+        XCTAssertEqual(stats.count, 100)
+        XCTAssertEqual(stats.p50, 50)
+        XCTAssertEqual(stats.p95, 95)
+        XCTAssertEqual(stats.p99, 99)
+    }
+    
+    func testLatencyStats_fewSamples_usesSlowestForHighPercentiles() throws {
+        let stats = try XCTUnwrap(LatencyStats(samples: [10, 40, 20, 30]))
+        
+        XCTAssertEqual(stats.p50, 20)
+        XCTAssertEqual(stats.p95, 40)
+        XCTAssertEqual(stats.p99, 40)
+    }
+    
+    func testLatencyStats_noSamples_isNil() {
+        XCTAssertNil(LatencyStats(samples: []))
+    }
+}
@@ -0,0 +1,114 @@
+/*╔══════════════════════════════════════════════════════════════╗
+  ║  ░  T Y P I N G   M O N I T O R   T E S T S  ░░░░░░░░░░░░░  ║
+  ╚══════════════════════════════════════════════════════════════╝
+*/
+
+import XCTest
+@testable import MindTypeCore
+
+@MainActor
+final class TypingMonitorTests: XCTestCase {
+    
+    func testSnapshot_roundTripThroughJSON_restoresIdenticalState() throws {
+        let original = TypingMonitor(pauseThresholdMs: 800)
+        original.minWords = 2
+        original.onFocus(text: "hello", caret: 5)
+        original.handleTextChange(newText: "hello wrld", caret: 10)
+        
+        let data = try JSONEncoder().encode(original.snapshot())
+        let decoded = try JSONDecoder().decode(TypingMonitor.Snapshot.self, from: data)
+        
+        let restored = TypingMonitor()
+        restored.restore(from: decoded)
+        
+        XCTAssertEqual(restored.buffer, "hello wrld")
+        XCTAssertEqual(restored.caretPosition, 10)
+        XCTAssertEqual(restored.pauseThresholdMs, 800)
+        XCTAssertEqual(restored.minWords, 2)
+        XCTAssertEqual(restored.rhythm.isBursting, original.rhythm.isBursting)
+        XCTAssertEqual(restored.markerState, original.markerState)
+    }
+    
+    func testRestore_unfocusedSnapshot_leavesMarkerDormant() {
+        let source = TypingMonitor()
+        let restored = TypingMonitor()
+        restored.onFocus(text: "abc", caret: 3)
+        
+        restored.restore(from: source.snapshot())
+        
+        XCTAssertEqual(restored.markerState, .dormant)
+        XCTAssertEqual(restored.buffer, "")
+    }
+    
+    private func correctionResult(for text: String) -> CorrectionWaveResult {
+        CorrectionWaveResult(
+            diffs: [CorrectionDiff(start: 6, end: 9, text: "the", stage: .noise, confidence: 0.9)],
+            activeRegion: TextRegion(start: 0, end: text.count),
+            durationMs: 1,
+            correctedText: text.replacingOccurrences(of: "teh", with: "the")
+        )
+    }
+    
+    func testRestore_whilePipelineRuns_dropsStaleCorrection() async {
+        let monitor = TypingMonitor()
+        let saved = monitor.snapshot()
+        var applied = 0
+        monitor.onFocus(text: "I saw teh cat today", caret: 19)
+        monitor.onCorrectionsApplied = { _, _, _ in applied += 1 }
+        monitor.onPauseDetected = { text, _ in
+            monitor.restore(from: saved)
+            return self.correctionResult(for: text)
+        }
+        
+        await monitor.forceCorrection()
+        
+        XCTAssertEqual(monitor.buffer, "")
+        XCTAssertEqual(monitor.markerState, .dormant)
+        XCTAssertEqual(applied, 0)
+    }
+    
+    func testRestore_duringSweep_abandonsSweep() async {
+        let monitor = TypingMonitor()
+        monitor.onFocus(text: "restored text here", caret: 18)
+        let saved = monitor.snapshot()
+        var applied = 0
+        monitor.onFocus(text: "I saw teh cat today", caret: 19)
+        monitor.onCorrectionsApplied = { _, _, _ in applied += 1 }
+        monitor.onPauseDetected = { text, _ in self.correctionResult(for: text) }
+        monitor.onSweepStart = { _ in monitor.restore(from: saved) }
+        
+        await monitor.forceCorrection()
+        
+        XCTAssertEqual(monitor.buffer, "restored text here")
+        XCTAssertEqual(monitor.rhythm, .idle)
+        XCTAssertEqual(monitor.markerState, .idle(position: 18))
+        XCTAssertEqual(applied, 0)
+    }
+    
+    func testActiveRegion_tracksLatestCaret() {
+        let monitor = TypingMonitor()
+        monitor.onFocus(text: "", caret: 0)
+        
+        monitor.handleTextChange(newText: "one two", caret: 7)
+        XCTAssertEqual(monitor.activeRegion(using: .default), TextRegion(start: 0, end: 7))
+        
+        monitor.handleTextChange(newText: "one two three", caret: 13)
+        XCTAssertEqual(monitor.activeRegion(using: .default), TextRegion(start: 0, end: 13))
+        
+        monitor.handleTextChange(newText: "one two three", caret: 3)
+        XCTAssertEqual(monitor.activeRegion(using: .default), TextRegion(start: 0, end: 3))
+    }
+    
+    func testActiveRegion_pipelinePolicy_honoursConfiguredWordCount() {
+        let pipeline = CorrectionPipeline(
+            lmAdapter: StubLMAdapter(),
+            config: PipelineConfiguration(activeRegionWords: 5)
+        )
+        let monitor = TypingMonitor()
+        monitor.onFocus(text: "", caret: 0)
+        
+        monitor.handleTextChange(newText: "one two three four five six seven", caret: 33)
+        
+        XCTAssertEqual(monitor.activeRegion(using: pipeline.activeRegionPolicy), TextRegion(start: 8, end: 33))
+    }
+}